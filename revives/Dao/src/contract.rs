@@ -1,6 +1,6 @@
 //! DAO 合约 — PolkaVM/wrevive 迁移版。
-//! 第一阶段先迁移构造、成员、ERC20、sudo 与通用 call 基础能力，
-//! 后续再补齐 proposal / vote / treasury 的完整治理流转。
+//! 包含成员与治理代币（ERC20）、sudo 与委员会、通用 call，
+//! 以及 proposal / vote / treasury 的完整治理流转。
 
 #![cfg_attr(not(test), no_std)]
 #![cfg_attr(not(test), no_main)]
@@ -13,43 +13,141 @@ static ALLOC: pvm_bump_allocator::BumpAllocator<65536> = pvm_bump_allocator::Bum
 
 mod curve;
 mod datas;
+mod erc20;
 mod errors;
+mod events;
 
+use alloy_core::primitives::keccak256;
 use pallet_revive_uapi::CallFlags;
-use wrevive_api::{Address, Env, Mapping, Storage, U256, Vec, env};
-use wrevive_macro::{mapping, revive_contract, storage};
+use wrevive_api::{
+    Address, BlockNumber, Encode, Env, List2D, Mapping, ReturnFlags, Storage, U256, Vec, env,
+};
+use wrevive_macro::{list_2d, mapping, revive_contract, storage};
 
 pub use curve::{Curve, CurveArg, Percent, arg_to_curve};
 pub use datas::{
-    Call, CallId, CallInput, Opinion, PropStatus, Selector, Spend, TokenInfo, Track, VoteInfo,
+    Batch, BatchMode, Call, CallId, CallInput, CallResult, Checkpoint, Conviction, ConvictionLevel,
+    Council, Delegation, Motion, Opinion, PropStatus, Proposal, Selector, Spend, SudoCall, Tally,
+    TallyInfo, TokenInfo, Track, TrackRuleKey, TransferPolicy, Vesting, VoteInfo,
 };
 pub use errors::Error;
+pub use events::Event;
 pub use primitives::{ensure, ok_or_err};
 
 #[revive_contract]
 pub mod dao {
     use super::*;
 
-    const EMPTY_TOPICS: &[[u8; 32]] = &[];
-
-    const MEMBERS: Storage<Vec<Address>> = storage!(b"members");
+    /// v1 及之前的成员列表，仅供 migrate 读取
+    const LEGACY_MEMBERS: Storage<Vec<Address>> = storage!(b"members");
+    const MEMBER_COUNT: Storage<u32> = storage!(b"member_count");
+    /// 成员下标 → 地址，下标连续，删除时用最后一个成员填补空位
+    const MEMBER_AT: Mapping<u32, Address> = mapping!(b"member_at");
+    const MEMBER_INDEX: Mapping<Address, u32> = mapping!(b"member_index");
     const PUBLIC_JOIN: Storage<bool> = storage!(b"public_join");
     const TOTAL_ISSUANCE: Storage<U256> = storage!(b"total_issuance");
     const SUDO_ACCOUNT: Storage<Option<Address>> = storage!(b"sudo_account");
     const TRANSFER_ENABLED: Storage<bool> = storage!(b"transfer_enabled");
+    const TRANSFER_MEMBERS_ONLY: Storage<bool> = storage!(b"transfer_members_only");
+    const TRANSFER_ALLOWLIST: Storage<Vec<Address>> = storage!(b"transfer_allowlist");
+    const FROZEN_ACCOUNTS: Storage<Vec<Address>> = storage!(b"frozen_accounts");
+    /// 成员未释放完的线性释放计划
+    const VESTINGS: Mapping<Address, Vec<Vesting>> = mapping!(b"vestings");
     const NEXT_TRACK_ID: Storage<u16> = storage!(b"next_track_id");
     const DEFAULT_TRACK: Storage<Option<u16>> = storage!(b"default_track");
 
     const MEMBER_BALANCES: Mapping<Address, U256> = mapping!(b"member_balances");
     const MEMBER_LOCK_BALANCES: Mapping<Address, U256> = mapping!(b"member_lock_balances");
     const ALLOWANCES: Mapping<(Address, Address), U256> = mapping!(b"allowances");
+    /// owner 下一个 permit 签名应使用的 nonce
+    const PERMIT_NONCES: Mapping<Address, u64> = mapping!(b"permit_nonces");
     const TOKENS: Mapping<u32, TokenInfo> = mapping!(b"tokens");
     const MEMBER_TOKENS: Mapping<(Address, u32), U256> = mapping!(b"member_tokens");
     const TRACKS: Mapping<u16, Track> = mapping!(b"tracks");
-    const TRACK_RULES: Mapping<(Option<Address>, Option<Selector>), u16> = mapping!(b"track_rules");
-    const SUDO_CALLS: Mapping<CallId, Call> = mapping!(b"sudo_calls");
+    const TRACK_RULES: Mapping<TrackRuleKey, u16> = mapping!(b"track_rules");
+    const TRACK_RULE_KEYS: Storage<Vec<TrackRuleKey>> = storage!(b"track_rule_keys");
+    const SUDO_CALLS: Mapping<CallId, SudoCall> = mapping!(b"sudo_calls");
     const NEXT_SUDO_CALL_ID: Storage<CallId> = storage!(b"next_sudo_call_id");
+    const COUNCILS: Mapping<u16, Council> = mapping!(b"councils");
+    const NEXT_COUNCIL_ID: Storage<u16> = storage!(b"next_council_id");
+    const MOTIONS: Mapping<u32, Motion> = mapping!(b"motions");
+    const NEXT_MOTION_ID: Storage<u32> = storage!(b"next_motion_id");
+    /// 正在执行的动议，供否决、快速通道等消息判断调用来源
+    const ENACTING_MOTION: Storage<Option<u32>> = storage!(b"enacting_motion");
+    /// 动议执行期间已批准的支出总额
+    const MOTION_SPENT: Mapping<u32, U256> = mapping!(b"motion_spent");
+    /// 可以否决提案、开启快速通道的紧急委员会
+    const EMERGENCY_COUNCIL: Storage<Option<u16>> = storage!(b"emergency_council");
+    /// 被否决的调用在冷却期内不能再次提交
+    const VETO_COOLDOWN: Storage<BlockNumber> = storage!(b"veto_cooldown");
+    /// 调用哈希 → 冷却结束区块
+    const VETOED_CALLS: Mapping<[u8; 32], BlockNumber> = mapping!(b"vetoed_calls");
+    /// 提案 → 快速通道时长
+    const FAST_TRACKS: Mapping<CallId, BlockNumber> = mapping!(b"fast_tracks");
     const REENTRANCY_GUARD: Storage<bool> = storage!(b"reentrancy_guard");
+    const PROPOSALS: Mapping<CallId, Proposal> = mapping!(b"proposals");
+    const NEXT_PROPOSAL_ID: Storage<CallId> = storage!(b"next_proposal_id");
+    const VOTES: Mapping<u64, VoteInfo> = mapping!(b"votes");
+    const NEXT_VOTE_ID: Storage<u64> = storage!(b"next_vote_id");
+    const VOTES_OF_MEMBER: List2D<Address, u64, u64> = list_2d!(b"votes_of_member");
+    /// 每个 track 正在决策中的提案数
+    const DECIDING_COUNT: Mapping<u16, BlockNumber> = mapping!(b"deciding_count");
+    /// 已缴押金但 track 决策名额已满、按缴押金顺序排队的提案
+    const DECIDING_QUEUE: Mapping<u16, Vec<CallId>> = mapping!(b"deciding_queue");
+    /// 已通过、等待执行的提案
+    const ENACTMENT_QUEUE: Storage<Vec<CallId>> = storage!(b"enactment_queue");
+    const NEXT_TOKEN_ID: Storage<u32> = storage!(b"next_token_id");
+    /// 非治理代币的总量，治理代币使用 TOTAL_ISSUANCE
+    const TOKEN_SUPPLY: Mapping<u32, U256> = mapping!(b"token_supply");
+    const TOKEN_ALLOWANCES: Mapping<(u32, Address, Address), U256> = mapping!(b"token_allowances");
+    /// (成员, 序号) → 余额检查点，按区块递增
+    const BALANCE_CHECKPOINTS: Mapping<(Address, u32), Checkpoint> =
+        mapping!(b"balance_checkpoints");
+    const BALANCE_CHECKPOINT_COUNT: Mapping<Address, u32> = mapping!(b"balance_checkpoint_count");
+    /// 序号 → 总发行量检查点
+    const SUPPLY_CHECKPOINTS: Mapping<u32, Checkpoint> = mapping!(b"supply_checkpoints");
+    const SUPPLY_CHECKPOINT_COUNT: Storage<u32> = storage!(b"supply_checkpoint_count");
+    /// 最新的快照编号，提交提案时递增
+    const SNAPSHOT_ID: Storage<u32> = storage!(b"snapshot_id");
+    /// track 自定义的 conviction 配置，未设置时使用默认配置
+    const TRACK_CONVICTIONS: Mapping<u16, Conviction> = mapping!(b"track_convictions");
+    /// (委托人, track) → 委托
+    const DELEGATIONS: Mapping<(Address, Option<u16>), Delegation> = mapping!(b"delegations");
+    /// 委托人已委托的 track 范围
+    const DELEGATION_SCOPES: Mapping<Address, Vec<Option<u16>>> = mapping!(b"delegation_scopes");
+    /// (受托人, track) → 委托人
+    const DELEGATORS: Mapping<(Address, Option<u16>), Vec<Address>> = mapping!(b"delegators");
+    /// 委托锁定的代币，多个 track 的委托共用同一份锁定
+    const DELEGATED_BALANCE: Mapping<Address, U256> = mapping!(b"delegated_balance");
+    const SPENDS: Mapping<u64, Spend> = mapping!(b"spends");
+    const NEXT_SPEND_ID: Storage<u64> = storage!(b"next_spend_id");
+    /// 合约余额中属于提案押金的部分，不可用于国库支出
+    const DEPOSIT_RESERVE: Storage<U256> = storage!(b"deposit_reserve");
+    /// 合约余额中待持币人领取的分红，不可用于国库支出
+    const REWARD_RESERVE: Storage<U256> = storage!(b"reward_reserve");
+    /// 每单位治理代币累计分红，放大 REWARD_PRECISION 倍
+    const REWARD_PER_TOKEN: Storage<U256> = storage!(b"reward_per_token");
+    const REWARD_PER_TOKEN_PAID: Mapping<Address, U256> = mapping!(b"reward_per_token_paid");
+    const PENDING_REWARDS: Mapping<Address, U256> = mapping!(b"pending_rewards");
+    /// 正在执行的提案，供 spend 等治理消息读取所属 track
+    const ENACTING_PROPOSAL: Storage<Option<CallId>> = storage!(b"enacting_proposal");
+    /// 提案执行期间已批准的国库支出总额，合计不得超过 track 的 max_balance
+    const PROPOSAL_SPENT: Mapping<CallId, U256> = mapping!(b"proposal_spent");
+    const MEMBER_PROPOSAL_VOTE: Mapping<(Address, CallId), u64> = mapping!(b"member_proposal_vote");
+    /// 批量提案的全部调用，提案的 call 字段为其中第一个调用
+    const PROPOSAL_BATCHES: Mapping<CallId, Batch> = mapping!(b"proposal_batches");
+
+    /// 存储版本，升级实现合约后由 migrate 迁移到当前版本
+    const STORAGE_VERSION: Storage<u32> = storage!(b"storage_version");
+    /// 分批迁移时旧成员列表中下一个待处理的下标
+    const MIGRATION_CURSOR: Storage<u32> = storage!(b"migration_cursor");
+
+    /// 分红累加器精度
+    const REWARD_PRECISION: u64 = 1_000_000_000_000_000_000;
+    /// 当前实现合约的存储版本
+    const CURRENT_STORAGE_VERSION: u32 = 2;
+    /// 以太坊 ecrecover 预编译合约地址
+    const ECRECOVER: [u8; 20] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
 
     #[revive(constructor)]
     pub fn new(
@@ -99,9 +197,44 @@ pub mod dao {
         init_state(users, public_join, sudo_account, Some(track))
     }
 
+    /// 部署在 revives/Proxy 之后时代替构造函数初始化，只有第一次调用生效
+    #[revive(message, write)]
+    pub fn init(
+        users: Vec<(Address, U256)>,
+        public_join: bool,
+        sudo_account: Option<Address>,
+        track: Option<Track>,
+    ) -> Result<(), Error> {
+        if TOKENS.get(&0).is_some() {
+            return Ok(());
+        }
+        init_state(users, public_join, sudo_account, track)
+    }
+
+    /// 全部成员，成员较多时请使用 members 分页查询
     #[revive(message)]
     pub fn list() -> Vec<Address> {
-        MEMBERS.get().unwrap_or_default()
+        let count = MEMBER_COUNT.get().unwrap_or(0);
+        let mut out = Vec::new();
+        for i in 0..count {
+            if let Some(member) = MEMBER_AT.get(&i) {
+                out.push(member);
+            }
+        }
+        out
+    }
+
+    #[revive(message)]
+    pub fn member_count() -> u32 {
+        MEMBER_COUNT.get().unwrap_or(0)
+    }
+
+    /// 按成员下标倒序分页，`start` 为 None 时从最后一个成员开始。
+    /// 成员退出时最后一个成员会移到其下标，翻页期间成员变动可能导致遗漏或重复。
+    #[revive(message)]
+    pub fn members(start: Option<u32>, size: u32) -> Vec<(u32, Address)> {
+        let total = MEMBER_COUNT.get().unwrap_or(0);
+        page_desc(total, start, size, |id| MEMBER_AT.get(&id))
     }
 
     #[revive(message)]
@@ -117,12 +250,14 @@ pub mod dao {
         );
         let caller = env().caller();
         ensure!(MEMBER_BALANCES.get(&caller).is_none(), Error::MemberExisted);
+        accrue_rewards(caller);
         MEMBER_BALANCES.set(&caller, &U256::ZERO);
         MEMBER_LOCK_BALANCES.set(&caller, &U256::ZERO);
-        let mut members = MEMBERS.get().unwrap_or_default();
-        members.push(caller);
-        MEMBERS.set(&members);
-        env().deposit_event(EMPTY_TOPICS, &caller.0.as_slice());
+        add_member(caller);
+        emit(Event::MemberJoined {
+            member: caller,
+            balance: U256::ZERO,
+        });
         Ok(())
     }
 
@@ -130,6 +265,7 @@ pub mod dao {
     pub fn set_public_join(public_join: bool) -> Result<(), Error> {
         ensure_from_gov()?;
         PUBLIC_JOIN.set(&public_join);
+        emit(Event::PublicJoinSet { public_join });
         Ok(())
     }
 
@@ -140,12 +276,18 @@ pub mod dao {
             MEMBER_BALANCES.get(&new_user).is_none(),
             Error::MemberExisted
         );
+        accrue_rewards(new_user);
         MEMBER_BALANCES.set(&new_user, &balance);
         MEMBER_LOCK_BALANCES.set(&new_user, &U256::ZERO);
         TOTAL_ISSUANCE.set(&(TOTAL_ISSUANCE.get().unwrap_or(U256::ZERO) + balance));
-        let mut members = MEMBERS.get().unwrap_or_default();
-        members.push(new_user);
-        MEMBERS.set(&members);
+        checkpoint_balance(new_user);
+        checkpoint_supply();
+        erc20::emit_transfer(Address::zero(), new_user, balance);
+        add_member(new_user);
+        emit(Event::MemberJoined {
+            member: new_user,
+            balance,
+        });
         Ok(())
     }
 
@@ -166,9 +308,11 @@ pub mod dao {
         );
         MEMBER_BALANCES.clear(&caller);
         MEMBER_LOCK_BALANCES.clear(&caller);
-        let mut members = MEMBERS.get().unwrap_or_default();
-        members.retain(|x| *x != caller);
-        MEMBERS.set(&members);
+        remove_member(caller);
+        emit(Event::MemberLeft {
+            member: caller,
+            burned: U256::ZERO,
+        });
         Ok(())
     }
 
@@ -179,16 +323,25 @@ pub mod dao {
             MEMBER_BALANCES.get(&caller).is_some(),
             Error::MemberNotExisted
         );
-        let amount = MEMBER_BALANCES.get(&caller).unwrap_or(U256::ZERO)
-            + MEMBER_LOCK_BALANCES.get(&caller).unwrap_or(U256::ZERO);
+        clear_delegations(caller);
+        // 锁定部分已包含在余额中，只销毁余额并清除锁定
+        // The lock is part of the balance: burn the balance and drop the lock
+        let amount = MEMBER_BALANCES.get(&caller).unwrap_or(U256::ZERO);
         let total = TOTAL_ISSUANCE.get().unwrap_or(U256::ZERO);
         ensure!(total >= amount, Error::LowBalance);
         TOTAL_ISSUANCE.set(&(total - amount));
+        accrue_rewards(caller);
         MEMBER_BALANCES.clear(&caller);
         MEMBER_LOCK_BALANCES.clear(&caller);
-        let mut members = MEMBERS.get().unwrap_or_default();
-        members.retain(|x| *x != caller);
-        MEMBERS.set(&members);
+        VESTINGS.clear(&caller);
+        checkpoint_balance(caller);
+        checkpoint_supply();
+        erc20::emit_transfer(caller, Address::zero(), amount);
+        remove_member(caller);
+        emit(Event::MemberLeft {
+            member: caller,
+            burned: amount,
+        });
         Ok(())
     }
 
@@ -199,19 +352,111 @@ pub mod dao {
             MEMBER_BALANCES.get(&user).is_some(),
             Error::MemberNotExisted
         );
-        let amount = MEMBER_BALANCES.get(&user).unwrap_or(U256::ZERO)
-            + MEMBER_LOCK_BALANCES.get(&user).unwrap_or(U256::ZERO);
+        clear_delegations(user);
+        // 锁定部分已包含在余额中，只销毁余额并清除锁定
+        // The lock is part of the balance: burn the balance and drop the lock
+        let amount = MEMBER_BALANCES.get(&user).unwrap_or(U256::ZERO);
         let total = TOTAL_ISSUANCE.get().unwrap_or(U256::ZERO);
         ensure!(total >= amount, Error::LowBalance);
         TOTAL_ISSUANCE.set(&(total - amount));
+        accrue_rewards(user);
         MEMBER_BALANCES.clear(&user);
         MEMBER_LOCK_BALANCES.clear(&user);
-        let mut members = MEMBERS.get().unwrap_or_default();
-        members.retain(|x| *x != user);
-        MEMBERS.set(&members);
+        VESTINGS.clear(&user);
+        checkpoint_balance(user);
+        checkpoint_supply();
+        erc20::emit_transfer(user, Address::zero(), amount);
+        remove_member(user);
+        emit(Event::MemberDeleted {
+            member: user,
+            burned: amount,
+        });
+        Ok(())
+    }
+
+    #[revive(message)]
+    pub fn vesting_of(owner: Address) -> Vec<Vesting> {
+        VESTINGS.get(&owner).unwrap_or_default()
+    }
+
+    /// 治理发放线性释放的代币：新铸造的代币全部锁定，按释放计划由成员领取解锁
+    #[revive(message, write)]
+    pub fn grant_vested(
+        to: Address,
+        amount: U256,
+        start: BlockNumber,
+        cliff: BlockNumber,
+        duration: BlockNumber,
+    ) -> Result<(), Error> {
+        ensure_from_gov()?;
+        ensure!(
+            amount > U256::ZERO && duration > 0 && cliff <= duration,
+            Error::InvalidVesting
+        );
+        let new_member = MEMBER_BALANCES.get(&to).is_none();
+        let balance = MEMBER_BALANCES.get(&to).unwrap_or(U256::ZERO);
+        accrue_rewards(to);
+        MEMBER_BALANCES.set(&to, &(balance + amount));
+        lock_balance(to, amount);
+        TOTAL_ISSUANCE.set(&(TOTAL_ISSUANCE.get().unwrap_or(U256::ZERO) + amount));
+        checkpoint_balance(to);
+        checkpoint_supply();
+        erc20::emit_transfer(Address::zero(), to, amount);
+        if new_member {
+            add_member(to);
+            emit(Event::MemberJoined {
+                member: to,
+                balance: amount,
+            });
+        }
+
+        let mut vestings = VESTINGS.get(&to).unwrap_or_default();
+        vestings.push(Vesting {
+            amount,
+            claimed: U256::ZERO,
+            start,
+            cliff,
+            duration,
+        });
+        VESTINGS.set(&to, &vestings);
+        emit(Event::VestingGranted {
+            to,
+            amount,
+            start,
+            cliff,
+            duration,
+        });
         Ok(())
     }
 
+    /// 领取所有释放计划中已释放的代币，返回本次解锁的数量
+    #[revive(message, write)]
+    pub fn claim_vested() -> Result<U256, Error> {
+        let caller = env().caller();
+        let mut vestings = VESTINGS.get(&caller).unwrap_or_default();
+        let now = env().block_number();
+        let mut claimable = U256::ZERO;
+        for vesting in vestings.iter_mut() {
+            let vested = vesting.vested(now);
+            claimable += vested - vesting.claimed;
+            vesting.claimed = vested;
+        }
+        ensure!(claimable > U256::ZERO, Error::NoVestedBalance);
+
+        vestings.retain(|v| v.claimed < v.amount);
+        if vestings.is_empty() {
+            VESTINGS.clear(&caller);
+        } else {
+            VESTINGS.set(&caller, &vestings);
+        }
+        unlock_balance(caller, claimable);
+        emit(Event::VestingClaimed {
+            who: caller,
+            amount: claimable,
+        });
+        Ok(claimable)
+    }
+
     #[revive(message)]
     pub fn total_supply() -> U256 {
         TOTAL_ISSUANCE.get().unwrap_or(U256::ZERO)
@@ -222,6 +467,24 @@ pub mod dao {
         MEMBER_BALANCES.get(&owner).unwrap_or(U256::ZERO)
     }
 
+    /// 成员在指定区块结束时的余额
+    #[revive(message)]
+    pub fn balance_of_at(owner: Address, block: BlockNumber) -> U256 {
+        let count = BALANCE_CHECKPOINT_COUNT.get(&owner).unwrap_or(0);
+        checkpoint_at(
+            count,
+            |c| c.block <= block,
+            |i| BALANCE_CHECKPOINTS.get(&(owner, i)),
+        )
+    }
+
+    /// 指定区块结束时的总发行量
+    #[revive(message)]
+    pub fn total_supply_at(block: BlockNumber) -> U256 {
+        let count = SUPPLY_CHECKPOINT_COUNT.get().unwrap_or(0);
+        checkpoint_at(count, |c| c.block <= block, |i| SUPPLY_CHECKPOINTS.get(&i))
+    }
+
     #[revive(message)]
     pub fn lock_balance_of(owner: Address) -> U256 {
         MEMBER_LOCK_BALANCES.get(&owner).unwrap_or(U256::ZERO)
@@ -239,25 +502,58 @@ pub mod dao {
             MEMBER_BALANCES.get(&caller).is_some(),
             Error::MemberNotExisted
         );
-        ALLOWANCES.set(&(caller, spender), &value);
+        set_allowance(caller, spender, value);
         Ok(())
     }
 
+    /// owner 下一个 permit 签名应使用的 nonce
+    #[revive(message)]
+    pub fn nonces(owner: Address) -> u64 {
+        PERMIT_NONCES.get(&owner).unwrap_or(0)
+    }
+
+    /// permit 签名的 EIP-712 域分隔符
+    #[revive(message)]
+    pub fn domain_separator() -> [u8; 32] {
+        erc20::permit_domain().separator().0
+    }
+
+    /// 凭 owner 对 EIP-712 Permit 的签名（r ‖ s ‖ v）设置授权，任何人都可以提交。
+    /// deadline 为秒级时间戳，签名使用后 owner 的 nonce 加一。
     #[revive(message, write)]
-    pub fn transfer(to: Address, value: U256) -> Result<(), Error> {
+    pub fn permit(
+        owner: Address,
+        spender: Address,
+        value: U256,
+        deadline: u64,
+        signature: [u8; 65],
+    ) -> Result<(), Error> {
+        erc20::ensure_permit_live(env().now(), deadline)?;
         ensure!(
-            TRANSFER_ENABLED.get().unwrap_or(true),
-            Error::TransferDisable
+            MEMBER_BALANCES.get(&owner).is_some(),
+            Error::MemberNotExisted
+        );
+        let nonce = PERMIT_NONCES.get(&owner).unwrap_or(0);
+        let hash = erc20::permit_hash(owner, spender, value, nonce, deadline);
+        ensure!(
+            ecdsa_recover(&hash, &signature) == Some(owner),
+            Error::InvalidSignature
         );
-        transfer_from_to(env().caller(), to, value)
+        PERMIT_NONCES.set(&owner, &(nonce + 1));
+        set_allowance(owner, spender, value);
+        Ok(())
+    }
+
+    #[revive(message, write)]
+    pub fn transfer(to: Address, value: U256) -> Result<(), Error> {
+        let caller = env().caller();
+        ensure_transfer_allowed(caller, to)?;
+        transfer_from_to(caller, to, value)
     }
 
     #[revive(message, write)]
     pub fn transfer_from(from: Address, to: Address, value: U256) -> Result<(), Error> {
-        ensure!(
-            TRANSFER_ENABLED.get().unwrap_or(true),
-            Error::TransferDisable
-        );
+        ensure_transfer_allowed(from, to)?;
         let caller = env().caller();
         let allowance = ALLOWANCES.get(&(from, caller)).unwrap_or(U256::ZERO);
         ensure!(allowance >= value, Error::InsufficientAllowance);
@@ -277,8 +573,70 @@ pub mod dao {
         ensure!(total >= value, Error::LowBalance);
         // --- Effects ---
         let balance = MEMBER_BALANCES.get(&caller).unwrap_or(U256::ZERO);
+        accrue_rewards(caller);
         MEMBER_BALANCES.set(&caller, &(balance - value));
         TOTAL_ISSUANCE.set(&(total - value));
+        checkpoint_balance(caller);
+        checkpoint_supply();
+        erc20::emit_transfer(caller, Address::zero(), value);
+        emit(Event::Burn {
+            from: caller,
+            amount: value,
+        });
+        Ok(())
+    }
+
+    #[revive(message)]
+    pub fn transfer_policy() -> TransferPolicy {
+        TransferPolicy {
+            enabled: TRANSFER_ENABLED.get().unwrap_or(true),
+            members_only: TRANSFER_MEMBERS_ONLY.get().unwrap_or(false),
+            allowlist: TRANSFER_ALLOWLIST.get().unwrap_or_default(),
+            frozen: FROZEN_ACCOUNTS.get().unwrap_or_default(),
+        }
+    }
+
+    /// 治理开关治理代币转账，关闭后代币不可转让（白名单地址除外）
+    #[revive(message, write)]
+    pub fn set_transfer_enabled(enabled: bool) -> Result<(), Error> {
+        ensure_from_gov()?;
+        TRANSFER_ENABLED.set(&enabled);
+        emit(Event::TransferPolicyChanged {
+            policy: transfer_policy(),
+        });
+        Ok(())
+    }
+
+    /// 治理设置是否只允许成员接收转账
+    #[revive(message, write)]
+    pub fn set_transfer_members_only(members_only: bool) -> Result<(), Error> {
+        ensure_from_gov()?;
+        TRANSFER_MEMBERS_ONLY.set(&members_only);
+        emit(Event::TransferPolicyChanged {
+            policy: transfer_policy(),
+        });
+        Ok(())
+    }
+
+    /// 治理把地址加入或移出转账白名单
+    #[revive(message, write)]
+    pub fn set_transfer_allowed(account: Address, allowed: bool) -> Result<(), Error> {
+        ensure_from_gov()?;
+        update_address_list(&TRANSFER_ALLOWLIST, account, allowed);
+        emit(Event::TransferPolicyChanged {
+            policy: transfer_policy(),
+        });
+        Ok(())
+    }
+
+    /// 治理冻结或解冻地址，冻结后不能转出也不能接收
+    #[revive(message, write)]
+    pub fn set_frozen(account: Address, frozen: bool) -> Result<(), Error> {
+        ensure_from_gov()?;
+        update_address_list(&FROZEN_ACCOUNTS, account, frozen);
+        emit(Event::TransferPolicyChanged {
+            policy: transfer_policy(),
+        });
         Ok(())
     }
 
@@ -287,8 +645,21 @@ pub mod dao {
         SUDO_ACCOUNT.get().unwrap_or(None)
     }
 
+    #[revive(message)]
+    pub fn sudo_call(call_id: CallId) -> Option<SudoCall> {
+        SUDO_CALLS.get(&call_id)
+    }
+
+    /// 按 sudo 调用 id 倒序分页，`start` 为 None 时从最新的调用开始
+    #[revive(message)]
+    pub fn sudo_calls(start: Option<CallId>, size: u32) -> Vec<(CallId, SudoCall)> {
+        let total = NEXT_SUDO_CALL_ID.get().unwrap_or(0);
+        page_desc(total, start, size, |id| SUDO_CALLS.get(&id))
+    }
+
+    /// sudo 账户直接执行调用，调用结果（含失败）记录在 sudo 调用历史中
     #[revive(message, write)]
-    pub fn sudo(call: Call) -> Result<Vec<u8>, Error> {
+    pub fn sudo(call: Call) -> Result<CallResult, Error> {
         let caller = env().caller();
         ensure!(
             SUDO_ACCOUNT.get().unwrap_or(None) == Some(caller),
@@ -301,12 +672,33 @@ pub mod dao {
             Error::ReentrantCall
         );
         REENTRANCY_GUARD.set(&true);
-        let call_id = NEXT_SUDO_CALL_ID.get().unwrap_or(0);
-        NEXT_SUDO_CALL_ID.set(&(call_id + 1));
-        SUDO_CALLS.set(&call_id, &call);
-        let result = exec_call_internal(call);
+        let result = exec_call_with_result(call.clone());
+        REENTRANCY_GUARD.set(&false);
+        record_sudo_call(caller, call, result.clone());
+        Ok(result)
+    }
+
+    /// sudo 账户批量执行调用，每个调用分别记录在 sudo 调用历史中
+    #[revive(message, write)]
+    pub fn sudo_batch(batch: Batch) -> Result<Vec<CallResult>, Error> {
+        let caller = env().caller();
+        ensure!(
+            SUDO_ACCOUNT.get().unwrap_or(None) == Some(caller),
+            Error::MustCallByGov
+        );
+        ensure!(!batch.calls.is_empty(), Error::InvalidBatch);
+        ensure!(
+            !REENTRANCY_GUARD.get().unwrap_or(false),
+            Error::ReentrantCall
+        );
+        REENTRANCY_GUARD.set(&true);
+        let results = exec_batch(&batch);
         REENTRANCY_GUARD.set(&false);
-        result
+        let results = results?;
+        for (call, result) in batch.calls.into_iter().zip(results.iter()) {
+            record_sudo_call(caller, call, result.clone());
+        }
+        Ok(results)
     }
 
     #[revive(message, write)]
@@ -317,6 +709,262 @@ pub mod dao {
             Error::MustCallByGov
         );
         SUDO_ACCOUNT.set(&None);
+        emit(Event::SudoRemoved { caller });
+        Ok(())
+    }
+
+    #[revive(message)]
+    pub fn council(council_id: u16) -> Option<Council> {
+        COUNCILS.get(&council_id)
+    }
+
+    #[revive(message)]
+    pub fn council_list() -> Vec<(u16, Council)> {
+        let total = NEXT_COUNCIL_ID.get().unwrap_or(0);
+        let mut out = Vec::new();
+        let mut i = 0u16;
+        while i < total {
+            if let Some(council) = COUNCILS.get(&i) {
+                out.push((i, council));
+            }
+            i += 1;
+        }
+        out
+    }
+
+    #[revive(message)]
+    pub fn is_council_member(council_id: u16, account: Address) -> bool {
+        COUNCILS
+            .get(&council_id)
+            .is_some_and(|council| council.members.contains(&account))
+    }
+
+    #[revive(message, write)]
+    pub fn add_council(council: Council) -> Result<u16, Error> {
+        ensure_from_gov()?;
+        ensure_valid_council(&council)?;
+        let council_id = NEXT_COUNCIL_ID.get().unwrap_or(0);
+        COUNCILS.set(&council_id, &council);
+        NEXT_COUNCIL_ID.set(&(council_id + 1));
+        emit(Event::CouncilSet { council_id });
+        Ok(council_id)
+    }
+
+    /// 治理修改委员会成员、门槛与允许的调用，未执行的动议按新配置计票
+    #[revive(message, write)]
+    pub fn edit_council(council_id: u16, council: Council) -> Result<(), Error> {
+        ensure_from_gov()?;
+        ensure_valid_council(&council)?;
+        ensure!(COUNCILS.get(&council_id).is_some(), Error::CouncilNotFound);
+        COUNCILS.set(&council_id, &council);
+        emit(Event::CouncilSet { council_id });
+        Ok(())
+    }
+
+    #[revive(message)]
+    pub fn motion(motion_id: u32) -> Option<Motion> {
+        MOTIONS.get(&motion_id)
+    }
+
+    /// 按动议 id 倒序分页，`start` 为 None 时从最新的动议开始
+    #[revive(message)]
+    pub fn motions(start: Option<u32>, size: u32) -> Vec<(u32, Motion)> {
+        let total = NEXT_MOTION_ID.get().unwrap_or(0);
+        page_desc(total, start, size, |id| MOTIONS.get(&id))
+    }
+
+    /// 委员会成员发起动议，发起人计为第一个批准
+    #[revive(message, write)]
+    pub fn propose_motion(council_id: u16, call: Call) -> Result<u32, Error> {
+        let caller = env().caller();
+        let council = COUNCILS.get(&council_id).ok_or(Error::CouncilNotFound)?;
+        ensure!(council.members.contains(&caller), Error::NotCouncilMember);
+        ensure!(council_allows(&council, &call), Error::CallNotAllowed);
+        let motion_id = NEXT_MOTION_ID.get().unwrap_or(0);
+        NEXT_MOTION_ID.set(&(motion_id + 1));
+        MOTIONS.set(
+            &motion_id,
+            &Motion {
+                council_id,
+                call,
+                proposer: caller,
+                approvals: [caller].into(),
+                executed: false,
+                result: None,
+            },
+        );
+        emit(Event::MotionProposed {
+            motion_id,
+            council_id,
+            proposer: caller,
+        });
+        Ok(motion_id)
+    }
+
+    /// 委员会成员批准动议，返回当前有效批准数
+    #[revive(message, write)]
+    pub fn approve_motion(motion_id: u32) -> Result<u32, Error> {
+        let caller = env().caller();
+        let mut motion = MOTIONS.get(&motion_id).ok_or(Error::MotionNotFound)?;
+        ensure!(!motion.executed, Error::MotionExecuted);
+        let council = COUNCILS
+            .get(&motion.council_id)
+            .ok_or(Error::CouncilNotFound)?;
+        ensure!(council.members.contains(&caller), Error::NotCouncilMember);
+        ensure!(
+            !motion.approvals.contains(&caller),
+            Error::MotionAlreadyApproved
+        );
+        motion.approvals.push(caller);
+        MOTIONS.set(&motion_id, &motion);
+        emit(Event::MotionApproved {
+            motion_id,
+            member: caller,
+        });
+        Ok(approval_count(&motion, &council))
+    }
+
+    /// 有效批准数达到门槛后由委员会成员执行动议，以 DAO 身份调用，结果（含失败）记录在动议上
+    #[revive(message, write)]
+    pub fn exec_motion(motion_id: u32) -> Result<CallResult, Error> {
+        let caller = env().caller();
+        let mut motion = MOTIONS.get(&motion_id).ok_or(Error::MotionNotFound)?;
+        ensure!(!motion.executed, Error::MotionExecuted);
+        let council = COUNCILS
+            .get(&motion.council_id)
+            .ok_or(Error::CouncilNotFound)?;
+        ensure!(council.members.contains(&caller), Error::NotCouncilMember);
+        // 委员会配置可能在发起后被修改，执行时按当前配置重新检查
+        // The council may have been edited since the motion was proposed, so re-check against it
+        ensure!(
+            council_allows(&council, &motion.call),
+            Error::CallNotAllowed
+        );
+        ensure!(
+            approval_count(&motion, &council) >= council.threshold,
+            Error::MotionNotApproved
+        );
+        ensure!(
+            !REENTRANCY_GUARD.get().unwrap_or(false),
+            Error::ReentrantCall
+        );
+        REENTRANCY_GUARD.set(&true);
+        motion.executed = true;
+        MOTIONS.set(&motion_id, &motion);
+        ENACTING_MOTION.set(&Some(motion_id));
+        let result = exec_call_with_result(motion.call.clone());
+        ENACTING_MOTION.set(&None);
+        REENTRANCY_GUARD.set(&false);
+
+        motion.result = Some(result.clone());
+        MOTIONS.set(&motion_id, &motion);
+        emit(Event::MotionExecuted {
+            motion_id,
+            success: result.success,
+        });
+        Ok(result)
+    }
+
+    /// 紧急委员会与否决冷却期
+    #[revive(message)]
+    pub fn emergency_council() -> (Option<u16>, BlockNumber) {
+        (
+            EMERGENCY_COUNCIL.get().unwrap_or(None),
+            VETO_COOLDOWN.get().unwrap_or(0),
+        )
+    }
+
+    #[revive(message, write)]
+    pub fn set_emergency_council(
+        council_id: Option<u16>,
+        cooldown: BlockNumber,
+    ) -> Result<(), Error> {
+        ensure_from_gov()?;
+        ensure!(
+            council_id.is_none_or(|id| COUNCILS.get(&id).is_some()),
+            Error::CouncilNotFound
+        );
+        EMERGENCY_COUNCIL.set(&council_id);
+        VETO_COOLDOWN.set(&cooldown);
+        emit(Event::EmergencyCouncilSet {
+            council_id,
+            cooldown,
+        });
+        Ok(())
+    }
+
+    /// 被否决调用的冷却结束区块，哈希为 (合约, 选择器, 参数, 金额) 编码后的 keccak256，合约为 None 时取 DAO 地址；
+    /// 批量为各调用哈希列表的 keccak256，否决批量时其中每个调用也被否决
+    #[revive(message)]
+    pub fn vetoed_until(call_hash: [u8; 32]) -> Option<BlockNumber> {
+        VETOED_CALLS.get(&call_hash)
+    }
+
+    #[revive(message)]
+    pub fn fast_track_of(prop_id: CallId) -> Option<BlockNumber> {
+        FAST_TRACKS.get(&prop_id)
+    }
+
+    /// 紧急委员会通过动议否决已通过、未执行的提案，退出执行队列，
+    /// 其调用在冷却期内不能再次提交
+    #[revive(message, write)]
+    pub fn veto_proposal(prop_id: CallId) -> Result<(), Error> {
+        ensure_from_emergency()?;
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        ensure!(
+            matches!(prop.status, PropStatus::Approved(_)) && !prop.executed,
+            Error::InvalidProposalStatus
+        );
+        let now = env().block_number();
+        prop.status = PropStatus::Vetoed(now);
+        PROPOSALS.set(&prop_id, &prop);
+        let mut queue = ENACTMENT_QUEUE.get().unwrap_or_default();
+        queue.retain(|id| *id != prop_id);
+        ENACTMENT_QUEUE.set(&queue);
+
+        let vetoed = proposal_call_hash(prop_id, &prop);
+        let until = now.saturating_add(VETO_COOLDOWN.get().unwrap_or(0));
+        VETOED_CALLS.set(&vetoed, &until);
+        // 批量中的每个调用也一并否决，拆分、重排或插入其他调用都不能绕过
+        // Veto each batched call too, so splitting, reordering or padding the batch does not help
+        if let Some(batch) = PROPOSAL_BATCHES.get(&prop_id) {
+            for call in batch.calls.iter() {
+                VETOED_CALLS.set(&call_hash(call), &until);
+            }
+        }
+        emit(Event::ProposalStatusChanged {
+            prop_id,
+            status: prop.status.clone(),
+        });
+        emit(Event::ProposalVetoed {
+            prop_id,
+            call_hash: vetoed,
+            until,
+        });
+        Ok(())
+    }
+
+    /// 紧急委员会通过动议为未结束的提案开启快速通道，
+    /// 准备期、决策期、确认期与执行等待期均不超过 period，通过阈值仍按 track 曲线计算
+    #[revive(message, write)]
+    pub fn fast_track(prop_id: CallId, period: BlockNumber) -> Result<(), Error> {
+        ensure_from_emergency()?;
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        ensure!(
+            prop.status == PropStatus::Pending || is_deciding(&prop.status),
+            Error::InvalidProposalStatus
+        );
+        let track = TRACKS.get(&prop.track_id).ok_or(Error::NoTrack)?;
+        ensure!(
+            period > 0 && period < track.decision_period,
+            Error::InvalidFastTrack
+        );
+        FAST_TRACKS.set(&prop_id, &period);
+        emit(Event::ProposalFastTracked { prop_id, period });
+
+        let track = proposal_track(prop_id, prop.track_id).ok_or(Error::NoTrack)?;
+        refresh_status(prop_id, &mut prop, &track);
+        PROPOSALS.set(&prop_id, &prop);
         Ok(())
     }
 
@@ -347,9 +995,11 @@ pub mod dao {
     #[revive(message, write)]
     pub fn add_track(track: Track) -> Result<u16, Error> {
         ensure_from_gov()?;
+        ensure_valid_curves(&track)?;
         let track_id = NEXT_TRACK_ID.get().unwrap_or(0);
         TRACKS.set(&track_id, &track);
         NEXT_TRACK_ID.set(&(track_id + 1));
+        emit(Event::TrackAdded { track_id });
         Ok(track_id)
     }
 
@@ -358,14 +1008,18 @@ pub mod dao {
         ensure_from_gov()?;
         ensure!(TRACKS.get(&track_id).is_some(), Error::NoTrack);
         DEFAULT_TRACK.set(&Some(track_id));
+        emit(Event::DefaultTrackSet { track_id });
         Ok(())
     }
 
     #[revive(message, write)]
     pub fn edit_track(track_id: u16, track: Track) -> Result<(), Error> {
         ensure_from_gov()?;
+        ensure_valid_curves(&track)?;
         ensure!(TRACKS.get(&track_id).is_some(), Error::NoTrack);
         TRACKS.set(&track_id, &track);
+        emit(Event::TrackEdited { track_id });
+        promote_queued(track_id, &track);
         Ok(())
     }
 
@@ -377,7 +1031,93 @@ pub mod dao {
     ) -> Result<(), Error> {
         ensure_from_gov()?;
         ensure!(TRACKS.get(&track_id).is_some(), Error::NoTrack);
+        let mut keys = TRACK_RULE_KEYS.get().unwrap_or_default();
+        if !keys.contains(&(contract, selector)) {
+            keys.push((contract, selector));
+            TRACK_RULE_KEYS.set(&keys);
+        }
         TRACK_RULES.set(&(contract, selector), &track_id);
+        emit(Event::TrackRuleSet {
+            contract,
+            selector,
+            track_id,
+        });
+        Ok(())
+    }
+
+    #[revive(message, write)]
+    pub fn remove_track_rule(
+        contract: Option<Address>,
+        selector: Option<Selector>,
+    ) -> Result<(), Error> {
+        ensure_from_gov()?;
+        ensure!(
+            TRACK_RULES.get(&(contract, selector)).is_some(),
+            Error::TrackRuleNotFound
+        );
+        TRACK_RULES.clear(&(contract, selector));
+        let mut keys = TRACK_RULE_KEYS.get().unwrap_or_default();
+        keys.retain(|k| *k != (contract, selector));
+        TRACK_RULE_KEYS.set(&keys);
+        emit(Event::TrackRuleRemoved { contract, selector });
+        Ok(())
+    }
+
+    /// 在给定区块（相对决策期开始）上采样曲线阈值，供前端绘制曲线
+    #[revive(message)]
+    pub fn curve_preview(
+        curve: Curve,
+        points: Vec<BlockNumber>,
+    ) -> Result<Vec<(BlockNumber, u32)>, Error> {
+        ensure!(curve.is_valid(), Error::InvalidCurve);
+        Ok(points.into_iter().map(|x| (x, curve.y(x))).collect())
+    }
+
+    /// track 在给定已过区块数上的 (区块, 最低赞成率, 最低支持率)
+    #[revive(message)]
+    pub fn track_thresholds(
+        track_id: u16,
+        elapsed: Vec<BlockNumber>,
+    ) -> Option<Vec<(BlockNumber, u32, u32)>> {
+        let track = TRACKS.get(&track_id)?;
+        Some(
+            elapsed
+                .into_iter()
+                .map(|x| (x, track.min_approval.y(x), track.min_support.y(x)))
+                .collect(),
+        )
+    }
+
+    #[revive(message)]
+    pub fn track_rules() -> Vec<(TrackRuleKey, u16)> {
+        let mut out = Vec::new();
+        for key in TRACK_RULE_KEYS.get().unwrap_or_default().into_iter() {
+            if let Some(track_id) = TRACK_RULES.get(&key) {
+                out.push((key, track_id));
+            }
+        }
+        out
+    }
+
+    /// 按 (合约, 选择器) → (合约, *) → (*, 选择器) → 默认 track 的顺序解析调用所属 track
+    #[revive(message)]
+    pub fn track_for_call(call: Call) -> Option<u16> {
+        resolve_track(&call)
+    }
+
+    #[revive(message)]
+    pub fn track_conviction(track_id: u16) -> Option<Conviction> {
+        let track = TRACKS.get(&track_id)?;
+        Some(conviction_of(track_id, &track))
+    }
+
+    #[revive(message, write)]
+    pub fn set_track_conviction(track_id: u16, conviction: Conviction) -> Result<(), Error> {
+        ensure_from_gov()?;
+        ensure!(TRACKS.get(&track_id).is_some(), Error::NoTrack);
+        ensure!(!conviction.levels.is_empty(), Error::InvalidConviction);
+        TRACK_CONVICTIONS.set(&track_id, &conviction);
+        emit(Event::TrackConvictionSet { track_id });
         Ok(())
     }
 
@@ -392,9 +1132,812 @@ pub mod dao {
     }
 
     #[revive(message)]
-    pub fn set_code(_code_hash: wrevive_api::H256) -> Result<(), Error> {
+    pub fn token_supply(token_id: u32) -> U256 {
+        TOKEN_SUPPLY.get(&token_id).unwrap_or(U256::ZERO)
+    }
+
+    #[revive(message)]
+    pub fn token_allowance(token_id: u32, owner: Address, spender: Address) -> U256 {
+        TOKEN_ALLOWANCES
+            .get(&(token_id, owner, spender))
+            .unwrap_or(U256::ZERO)
+    }
+
+    /// 治理创建新代币（徽章、声誉等），id 从 1 开始，0 为治理代币
+    #[revive(message, write)]
+    pub fn create_token(info: TokenInfo) -> Result<u32, Error> {
         ensure_from_gov()?;
-        Err(Error::SetCodeFailed)
+        let token_id = NEXT_TOKEN_ID.get().unwrap_or(1);
+        NEXT_TOKEN_ID.set(&(token_id + 1));
+        TOKENS.set(&token_id, &info);
+        emit(Event::TokenCreated { token_id, info });
+        Ok(token_id)
+    }
+
+    #[revive(message, write)]
+    pub fn mint_token(token_id: u32, to: Address, amount: U256) -> Result<(), Error> {
+        ensure_from_gov()?;
+        ensure_secondary_token(token_id)?;
+        let balance = MEMBER_TOKENS.get(&(to, token_id)).unwrap_or(U256::ZERO);
+        MEMBER_TOKENS.set(&(to, token_id), &(balance + amount));
+        let supply = TOKEN_SUPPLY.get(&token_id).unwrap_or(U256::ZERO);
+        TOKEN_SUPPLY.set(&token_id, &(supply + amount));
+        emit(Event::TokenMinted {
+            token_id,
+            to,
+            amount,
+        });
+        Ok(())
+    }
+
+    #[revive(message, write)]
+    pub fn burn_token(token_id: u32, amount: U256) -> Result<(), Error> {
+        ensure_secondary_token(token_id)?;
+        let caller = env().caller();
+        let balance = MEMBER_TOKENS.get(&(caller, token_id)).unwrap_or(U256::ZERO);
+        ensure!(balance >= amount, Error::LowBalance);
+        MEMBER_TOKENS.set(&(caller, token_id), &(balance - amount));
+        let supply = TOKEN_SUPPLY.get(&token_id).unwrap_or(U256::ZERO);
+        TOKEN_SUPPLY.set(&token_id, &supply.saturating_sub(amount));
+        emit(Event::TokenBurned {
+            token_id,
+            from: caller,
+            amount,
+        });
+        Ok(())
+    }
+
+    #[revive(message, write)]
+    pub fn transfer_token(token_id: u32, to: Address, amount: U256) -> Result<(), Error> {
+        ensure_secondary_token(token_id)?;
+        token_transfer_from_to(token_id, env().caller(), to, amount)
+    }
+
+    #[revive(message, write)]
+    pub fn approve_token(token_id: u32, spender: Address, amount: U256) -> Result<(), Error> {
+        ensure_secondary_token(token_id)?;
+        let owner = env().caller();
+        TOKEN_ALLOWANCES.set(&(token_id, owner, spender), &amount);
+        emit(Event::TokenApproval {
+            token_id,
+            owner,
+            spender,
+            amount,
+        });
+        Ok(())
+    }
+
+    #[revive(message, write)]
+    pub fn transfer_token_from(
+        token_id: u32,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Error> {
+        ensure_secondary_token(token_id)?;
+        let caller = env().caller();
+        let allowance = TOKEN_ALLOWANCES
+            .get(&(token_id, from, caller))
+            .unwrap_or(U256::ZERO);
+        ensure!(allowance >= amount, Error::InsufficientAllowance);
+        TOKEN_ALLOWANCES.set(&(token_id, from, caller), &(allowance - amount));
+        token_transfer_from_to(token_id, from, to, amount)
+    }
+
+    #[revive(message)]
+    pub fn proposal(prop_id: CallId) -> Option<Proposal> {
+        PROPOSALS.get(&prop_id)
+    }
+
+    /// 按 proposal id 倒序分页，`start` 为 None 时从最新的提案开始
+    #[revive(message)]
+    pub fn proposals(start: Option<CallId>, size: u32) -> Vec<(CallId, Proposal)> {
+        let total = NEXT_PROPOSAL_ID.get().unwrap_or(0);
+        page_desc(total, start, size, |id| PROPOSALS.get(&id))
+    }
+
+    /// 成员提交提案，track 由调用目标按 track 规则解析，进入 Pending 状态，等待准备期结束后缴纳决策押金
+    #[revive(message, write)]
+    pub fn submit_proposal(call: Call) -> Result<CallId, Error> {
+        ensure_not_vetoed(call_hash(&call))?;
+        let track_id = resolve_track(&call).ok_or(Error::NoTrack)?;
+        create_proposal(call, track_id)
+    }
+
+    /// 提交批量提案，所有调用须解析到同一 track
+    #[revive(message, write)]
+    pub fn submit_batch_proposal(batch: Batch) -> Result<CallId, Error> {
+        let first = batch.calls.first().cloned().ok_or(Error::InvalidBatch)?;
+        let track_id = resolve_track(&first).ok_or(Error::NoTrack)?;
+        ensure!(
+            batch
+                .calls
+                .iter()
+                .all(|call| resolve_track(call) == Some(track_id)),
+            Error::InvalidBatch
+        );
+        // 被否决的单个调用不能混入批量中重新提交
+        // A vetoed call cannot come back wrapped in a batch
+        for call in batch.calls.iter() {
+            ensure_not_vetoed(call_hash(call))?;
+        }
+        ensure_not_vetoed(batch_hash(&batch))?;
+        let prop_id = create_proposal(first, track_id)?;
+        PROPOSAL_BATCHES.set(&prop_id, &batch);
+        Ok(prop_id)
+    }
+
+    #[revive(message)]
+    pub fn proposal_batch(prop_id: CallId) -> Option<Batch> {
+        PROPOSAL_BATCHES.get(&prop_id)
+    }
+
+    /// 提案人缴纳决策押金（随调用转入原生代币），提案进入 Ongoing 决策期
+    #[revive(message, write)]
+    pub fn deposit_proposal(prop_id: CallId) -> Result<(), Error> {
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        ensure!(prop.caller == env().caller(), Error::InvalidProposalCaller);
+        ensure!(
+            prop.status == PropStatus::Pending,
+            Error::InvalidProposalStatus
+        );
+        let mut queue = DECIDING_QUEUE.get(&prop.track_id).unwrap_or_default();
+        ensure!(!queue.contains(&prop_id), Error::InvalidProposalStatus);
+        let track = proposal_track(prop_id, prop.track_id).ok_or(Error::NoTrack)?;
+        let now = env().block_number();
+        ensure!(
+            now >= prop.submit_block.saturating_add(track.prepare_period),
+            Error::InvalidDepositTime
+        );
+        let value = env().value_transferred();
+        ensure!(value >= track.decision_deposit, Error::InvalidDeposit);
+        prop.deposit = value;
+        let reserve = DEPOSIT_RESERVE.get().unwrap_or(U256::ZERO);
+        DEPOSIT_RESERVE.set(&(reserve + value));
+
+        // 决策名额已满时排队，等待其他提案结束后自动进入决策期
+        // Queue when the track is full; promoted once another proposal leaves deciding
+        let deciding = DECIDING_COUNT.get(&prop.track_id).unwrap_or(0);
+        if deciding < track.max_deciding {
+            DECIDING_COUNT.set(&prop.track_id, &(deciding + 1));
+            prop.status = PropStatus::Ongoing;
+            prop.decide_block = Some(now);
+        } else {
+            queue.push(prop_id);
+            DECIDING_QUEUE.set(&prop.track_id, &queue);
+        }
+        PROPOSALS.set(&prop_id, &prop);
+        emit(Event::ProposalDeposited {
+            prop_id,
+            amount: value,
+        });
+        if prop.status == PropStatus::Ongoing {
+            emit(Event::ProposalStatusChanged {
+                prop_id,
+                status: prop.status.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// 提案当前的赞成率、支持率以及曲线阈值
+    #[revive(message)]
+    pub fn proposal_tally(prop_id: CallId) -> Option<TallyInfo> {
+        let prop = PROPOSALS.get(&prop_id)?;
+        let track = proposal_track(prop_id, prop.track_id)?;
+        Some(tally_info(&prop, &track))
+    }
+
+    /// 按当前区块推进提案状态，任何人都可以调用
+    #[revive(message, write)]
+    pub fn confirm_proposal(prop_id: CallId) -> Result<PropStatus, Error> {
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        let track = proposal_track(prop_id, prop.track_id).ok_or(Error::NoTrack)?;
+        refresh_status(prop_id, &mut prop, &track);
+        PROPOSALS.set(&prop_id, &prop);
+        Ok(prop.status)
+    }
+
+    /// track 当前决策中的提案数与排队中的提案
+    #[revive(message)]
+    pub fn deciding_of(track_id: u16) -> (BlockNumber, Vec<CallId>) {
+        (
+            DECIDING_COUNT.get(&track_id).unwrap_or(0),
+            DECIDING_QUEUE.get(&track_id).unwrap_or_default(),
+        )
+    }
+
+    /// 等待执行的提案及其最早可执行区块
+    #[revive(message)]
+    pub fn enactment_queue() -> Vec<(CallId, BlockNumber)> {
+        let mut out = Vec::new();
+        for prop_id in ENACTMENT_QUEUE.get().unwrap_or_default().into_iter() {
+            let block = PROPOSALS
+                .get(&prop_id)
+                .and_then(|prop| enactment_block(prop_id, &prop));
+            if let Some(block) = block {
+                out.push((prop_id, block));
+            }
+        }
+        out
+    }
+
+    /// 治理撤销未结束的提案，退还决策押金
+    #[revive(message, write)]
+    pub fn cancel_proposal(prop_id: CallId) -> Result<(), Error> {
+        ensure_from_gov()?;
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        leave_decision(prop_id, &prop)?;
+        let deposit = take_deposit(&mut prop);
+        prop.status = PropStatus::Canceled;
+        PROPOSALS.set(&prop_id, &prop);
+        if deposit > U256::ZERO {
+            env()
+                .transfer(&prop.caller, &deposit)
+                .map_err(|_| Error::TransferFailed)?;
+        }
+        emit(Event::ProposalStatusChanged {
+            prop_id,
+            status: prop.status.clone(),
+        });
+        Ok(())
+    }
+
+    /// 治理终止恶意提案，决策押金没收进国库
+    #[revive(message, write)]
+    pub fn kill_proposal(prop_id: CallId) -> Result<(), Error> {
+        ensure_from_gov()?;
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        leave_decision(prop_id, &prop)?;
+        // 押金留在合约余额中，不再计入保留部分即归入国库
+        // The deposit stays in the contract balance; dropping the reserve hands it to the treasury
+        take_deposit(&mut prop);
+        prop.status = PropStatus::Killed;
+        PROPOSALS.set(&prop_id, &prop);
+        emit(Event::ProposalStatusChanged {
+            prop_id,
+            status: prop.status.clone(),
+        });
+        Ok(())
+    }
+
+    /// 提案通过或被拒绝后，提案人取回决策押金
+    #[revive(message, write)]
+    pub fn refund_deposit(prop_id: CallId) -> Result<(), Error> {
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        ensure!(prop.caller == env().caller(), Error::InvalidProposalCaller);
+        ensure!(
+            matches!(
+                prop.status,
+                PropStatus::Approved(_) | PropStatus::Rejected(_) | PropStatus::Vetoed(_)
+            ),
+            Error::PropNotEnd
+        );
+        ensure!(prop.deposit > U256::ZERO, Error::InvalidDeposit);
+        let deposit = take_deposit(&mut prop);
+        PROPOSALS.set(&prop_id, &prop);
+        env()
+            .transfer(&prop.caller, &deposit)
+            .map_err(|_| Error::TransferFailed)?;
+        emit(Event::DepositRefunded {
+            prop_id,
+            to: prop.caller,
+            amount: deposit,
+        });
+        Ok(())
+    }
+
+    /// 执行已通过且过了最短执行等待期的提案，调用结果（含失败）记录在提案上。
+    /// 批量提案的 output 为各调用结果的编码；AllOrNothing 批量中任一调用失败则整体回滚，提案仍可再次执行。
+    #[revive(message, write)]
+    pub fn exec_proposal(prop_id: CallId) -> Result<CallResult, Error> {
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        ensure!(
+            matches!(prop.status, PropStatus::Approved(_)),
+            Error::ProposalNotConfirmed
+        );
+        ensure!(!prop.executed, Error::InvalidProposalStatus);
+        let now = env().block_number();
+        let ready = enactment_block(prop_id, &prop).ok_or(Error::NoTrack)?;
+        ensure!(now >= ready, Error::InvalidEnactmentTime);
+        ensure!(
+            !REENTRANCY_GUARD.get().unwrap_or(false),
+            Error::ReentrantCall
+        );
+        REENTRANCY_GUARD.set(&true);
+        ENACTING_PROPOSAL.set(&Some(prop_id));
+        let result = match PROPOSAL_BATCHES.get(&prop_id) {
+            Some(batch) => exec_batch(&batch).map(|results| CallResult {
+                block: now,
+                success: results.iter().all(|r| r.success),
+                output: results.encode(),
+            }),
+            None => Ok(exec_call_with_result(prop.call.clone())),
+        };
+        ENACTING_PROPOSAL.set(&None);
+        REENTRANCY_GUARD.set(&false);
+        // 失败时不写入执行状态，提案留在队列中可再次执行
+        // Nothing is persisted on failure, the proposal stays queued for a retry
+        let result = result?;
+
+        prop.executed = true;
+        prop.result = Some(result.clone());
+        PROPOSALS.set(&prop_id, &prop);
+        let mut queue = ENACTMENT_QUEUE.get().unwrap_or_default();
+        queue.retain(|id| *id != prop_id);
+        ENACTMENT_QUEUE.set(&queue);
+        emit(Event::ProposalExecuted {
+            prop_id,
+            success: result.success,
+        });
+        Ok(result)
+    }
+
+    #[revive(message)]
+    pub fn vote_info(vote_id: u64) -> Option<VoteInfo> {
+        VOTES.get(&vote_id)
+    }
+
+    /// 按投票时间倒序分页列出成员的投票记录
+    #[revive(message)]
+    pub fn votes_of(owner: Address, start: Option<u64>, size: u32) -> Vec<(u64, VoteInfo)> {
+        let ids = VOTES_OF_MEMBER.desc_list(&owner, start, size);
+        let mut out = Vec::new();
+        for (_k2, vote_id) in ids.into_iter() {
+            if let Some(vote) = VOTES.get(&vote_id) {
+                out.push((vote_id, vote));
+            }
+        }
+        out
+    }
+
+    /// 成员质押 DAO 代币对决策期内的提案投票，质押部分锁定到提案结束且过了解锁区块
+    #[revive(message, write)]
+    pub fn vote(
+        prop_id: CallId,
+        opinion: Opinion,
+        pledge: U256,
+        conviction: u8,
+    ) -> Result<u64, Error> {
+        let caller = env().caller();
+        ensure!(
+            MEMBER_BALANCES.get(&caller).is_some(),
+            Error::MemberNotExisted
+        );
+        let mut prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+        ensure!(is_deciding(&prop.status), Error::PropNotOngoing);
+        // 已委托的成员由受托人代为投票，避免同一份余额计入两次
+        // Delegators vote through their delegate so the same balance never counts twice
+        ensure!(
+            effective_delegation(caller, prop.track_id).is_none(),
+            Error::VoteDelegated
+        );
+        let track = proposal_track(prop_id, prop.track_id).ok_or(Error::NoTrack)?;
+        let now = env().block_number();
+        let decide_end = prop
+            .decide_block
+            .unwrap_or(now)
+            .saturating_add(track.decision_period);
+        // 确认期可以越过决策期终点，其余情况下决策期结束后不再接受投票
+        // Confirmation may run past the decision deadline; otherwise voting closes with it
+        ensure!(
+            prop.status == PropStatus::Confirming || now < decide_end,
+            Error::InvalidVoteTime
+        );
+        ensure!(
+            MEMBER_PROPOSAL_VOTE.get(&(caller, prop_id)).is_none(),
+            Error::InvalidVote
+        );
+        ensure!(free_balance(caller) >= pledge, Error::LowBalance);
+        // 质押不得超过提案提交时的余额快照，防止投票期间转账刷票
+        // Pledges are capped by the snapshot at submission so tokens moved mid-vote don't count twice
+        ensure!(
+            balance_at_snapshot(caller, prop.snapshot) >= pledge,
+            Error::LowBalance
+        );
+        // 计入委托给投票人的权重，并把相关委托锁定到本次投票解锁
+        // Count weight delegated to the voter and keep those delegations until this vote unlocks
+        let delegated = delegated_power(
+            caller,
+            prop.track_id,
+            Some(decide_end),
+            Some((prop_id, prop.snapshot)),
+        );
+        ensure!(pledge + delegated > U256::ZERO, Error::InvalidVote);
+        let config = conviction_of(prop.track_id, &track);
+        let level = config
+            .levels
+            .get(conviction as usize)
+            .ok_or(Error::InvalidConviction)?;
+        // conviction 只放大投票人自己的质押，委托权重按 1x 计
+        // Conviction only scales the voter's own pledge; delegated weight counts at 1x
+        let weight = pledge * U256::from(u64::from(level.multiplier)) / U256::from(10000u64);
+        let unlock_block =
+            decide_end.saturating_add(level.lock_periods.saturating_mul(config.lock_period));
+
+        lock_balance(caller, pledge);
+        let vote = VoteInfo {
+            pledge,
+            opinion,
+            vote_weight: weight + delegated,
+            delegated,
+            conviction,
+            unlock_block,
+            call_id: prop_id,
+            calller: caller,
+            vote_block: now,
+            deleted: false,
+        };
+        add_to_tally(&mut prop.tally, &vote);
+        let vote_id = NEXT_VOTE_ID.get().unwrap_or(0);
+        NEXT_VOTE_ID.set(&(vote_id + 1));
+        VOTES.set(&vote_id, &vote);
+        VOTES_OF_MEMBER.insert(&caller, &vote_id);
+        MEMBER_PROPOSAL_VOTE.set(&(caller, prop_id), &vote_id);
+        emit(Event::Voted {
+            vote_id,
+            prop_id,
+            voter: caller,
+            opinion: vote.opinion.clone(),
+            vote_weight: vote.vote_weight,
+        });
+
+        refresh_status(prop_id, &mut prop, &track);
+        PROPOSALS.set(&prop_id, &prop);
+        Ok(vote_id)
+    }
+
+    /// 决策期内撤回投票，立即释放质押
+    #[revive(message, write)]
+    pub fn cancel_vote(vote_id: u64) -> Result<(), Error> {
+        let caller = env().caller();
+        let mut vote = VOTES.get(&vote_id).ok_or(Error::InvalidVote)?;
+        ensure!(vote.calller == caller, Error::InvalidVoteUser);
+        ensure!(!vote.deleted, Error::VoteAlreadyUnlocked);
+        let mut prop = PROPOSALS.get(&vote.call_id).ok_or(Error::InvalidProposal)?;
+        ensure!(is_deciding(&prop.status), Error::InvalidVoteStatus);
+        let track = proposal_track(vote.call_id, prop.track_id).ok_or(Error::NoTrack)?;
+
+        remove_from_tally(&mut prop.tally, &vote);
+        unlock_balance(caller, vote.pledge);
+        vote.deleted = true;
+        VOTES.set(&vote_id, &vote);
+        MEMBER_PROPOSAL_VOTE.clear(&(caller, vote.call_id));
+        emit(Event::VoteCanceled {
+            vote_id,
+            voter: caller,
+        });
+
+        refresh_status(vote.call_id, &mut prop, &track);
+        PROPOSALS.set(&vote.call_id, &prop);
+        Ok(())
+    }
+
+    /// 提案结束且到达解锁区块后，释放投票质押
+    #[revive(message, write)]
+    pub fn unlock(vote_id: u64) -> Result<(), Error> {
+        let caller = env().caller();
+        let mut vote = VOTES.get(&vote_id).ok_or(Error::InvalidVote)?;
+        ensure!(vote.calller == caller, Error::InvalidVoteUser);
+        ensure!(!vote.deleted, Error::VoteAlreadyUnlocked);
+        let prop = PROPOSALS.get(&vote.call_id).ok_or(Error::InvalidProposal)?;
+        ensure!(!is_deciding(&prop.status), Error::ProposalInDecision);
+        ensure!(
+            env().block_number() >= vote.unlock_block,
+            Error::InvalidVoteUnlockTime
+        );
+
+        unlock_balance(caller, vote.pledge);
+        vote.deleted = true;
+        VOTES.set(&vote_id, &vote);
+        emit(Event::VoteUnlocked {
+            vote_id,
+            voter: caller,
+            amount: vote.pledge,
+        });
+        Ok(())
+    }
+
+    /// 成员发出的委托
+    #[revive(message)]
+    pub fn delegations_of(owner: Address) -> Vec<Delegation> {
+        let mut out = Vec::new();
+        for scope in DELEGATION_SCOPES
+            .get(&owner)
+            .unwrap_or_default()
+            .into_iter()
+        {
+            if let Some(delegation) = DELEGATIONS.get(&(owner, scope)) {
+                out.push(delegation);
+            }
+        }
+        out
+    }
+
+    /// 成员在 track 上直接收到的委托权重
+    #[revive(message)]
+    pub fn delegated_power_of(owner: Address, track_id: u16) -> U256 {
+        delegated_power(owner, track_id, None, None)
+    }
+
+    /// 把投票权委托给其他成员，track_id 为 None 时对所有 track 生效。
+    /// 首次委托锁定全部可用余额，其他 track 的委托共用这份锁定。
+    /// 委托只有一跳：不能委托给已委托他人的成员，已收到委托的成员也不能再委托。
+    #[revive(message, write)]
+    pub fn delegate(to: Address, track_id: Option<u16>) -> Result<(), Error> {
+        let caller = env().caller();
+        ensure!(
+            MEMBER_BALANCES.get(&caller).is_some(),
+            Error::MemberNotExisted
+        );
+        ensure!(MEMBER_BALANCES.get(&to).is_some(), Error::MemberNotExisted);
+        ensure!(to != caller, Error::InvalidDelegate);
+        if let Some(track_id) = track_id {
+            ensure!(TRACKS.get(&track_id).is_some(), Error::NoTrack);
+        }
+        ensure!(
+            DELEGATIONS.get(&(caller, track_id)).is_none(),
+            Error::DelegationExisted
+        );
+        // 委托只有一跳，否则链上的权重不计入任何投票
+        // Delegation is a single hop; longer chains would leave weight uncounted
+        ensure!(
+            !is_delegating(to, track_id) && !has_delegators(caller, track_id),
+            Error::DelegationTooDeep
+        );
+        // 全局委托已被投票锁定时，不能改由单 track 委托在同一提案上再计一次
+        // A global delegation locked by a vote cannot be overridden on a single track
+        if track_id.is_some() {
+            let locked = DELEGATIONS
+                .get(&(caller, None))
+                .is_some_and(|d| env().block_number() < d.locked_until);
+            ensure!(!locked, Error::DelegationLocked);
+        }
+
+        let amount = match DELEGATED_BALANCE.get(&caller) {
+            Some(amount) => amount,
+            None => {
+                let amount = free_balance(caller);
+                ensure!(amount > U256::ZERO, Error::LowBalance);
+                lock_balance(caller, amount);
+                DELEGATED_BALANCE.set(&caller, &amount);
+                amount
+            }
+        };
+        let delegation = Delegation {
+            to,
+            track_id,
+            amount,
+            block: env().block_number(),
+            locked_until: 0,
+        };
+        DELEGATIONS.set(&(caller, track_id), &delegation);
+        let mut scopes = DELEGATION_SCOPES.get(&caller).unwrap_or_default();
+        scopes.push(track_id);
+        DELEGATION_SCOPES.set(&caller, &scopes);
+        let mut delegators = DELEGATORS.get(&(to, track_id)).unwrap_or_default();
+        delegators.push(caller);
+        DELEGATORS.set(&(to, track_id), &delegators);
+        emit(Event::Delegated {
+            from: caller,
+            to,
+            track_id,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// 撤销委托；委托权重仍计入未解锁的投票时不可撤销。最后一个委托撤销后释放锁定。
+    #[revive(message, write)]
+    pub fn undelegate(track_id: Option<u16>) -> Result<(), Error> {
+        let caller = env().caller();
+        let delegation = DELEGATIONS
+            .get(&(caller, track_id))
+            .ok_or(Error::DelegationNotFound)?;
+        ensure!(
+            env().block_number() >= delegation.locked_until,
+            Error::DelegationLocked
+        );
+        clear_delegation(caller, &delegation);
+        Ok(())
+    }
+
+    /// 国库可用余额（合约余额扣除提案押金）
+    #[revive(message)]
+    pub fn treasury_balance() -> U256 {
+        treasury_free_balance()
+    }
+
+    #[revive(message)]
+    pub fn get_spend(spend_id: u64) -> Option<Spend> {
+        SPENDS.get(&spend_id)
+    }
+
+    /// 按 spend id 倒序分页，`start` 为 None 时从最新的支出开始
+    #[revive(message)]
+    pub fn spends(start: Option<u64>, size: u32) -> Vec<(u64, Spend)> {
+        let total = NEXT_SPEND_ID.get().unwrap_or(0);
+        page_desc(total, start, size, |id| SPENDS.get(&id))
+    }
+
+    /// 治理批准一笔国库支出；通过提案执行时合计不得超过 track 的 max_balance，通过动议执行时不得超过委员会的 max_spend
+    #[revive(message, write)]
+    pub fn spend(to: Address, amount: U256) -> Result<u64, Error> {
+        ensure_from_gov()?;
+        let caller = match ENACTING_PROPOSAL.get().unwrap_or(None) {
+            Some(prop_id) => {
+                let prop = PROPOSALS.get(&prop_id).ok_or(Error::InvalidProposal)?;
+                let track = TRACKS.get(&prop.track_id).ok_or(Error::NoTrack)?;
+                // 批量提案中的多笔支出合计计入限额
+                // Spends within one (batch) enactment count against the limit together
+                let spent = PROPOSAL_SPENT.get(&prop_id).unwrap_or(U256::ZERO) + amount;
+                ensure!(spent <= track.max_balance, Error::MaxBalanceOverflow);
+                PROPOSAL_SPENT.set(&prop_id, &spent);
+                prop.caller
+            }
+            None => match ENACTING_MOTION.get().unwrap_or(None) {
+                // 委员会动议的支出计入该委员会的限额
+                // Spends enacted by a council motion count against the council's limit
+                Some(motion_id) => {
+                    let motion = MOTIONS.get(&motion_id).ok_or(Error::MotionNotFound)?;
+                    let council = COUNCILS
+                        .get(&motion.council_id)
+                        .ok_or(Error::CouncilNotFound)?;
+                    let spent = MOTION_SPENT.get(&motion_id).unwrap_or(U256::ZERO) + amount;
+                    ensure!(spent <= council.max_spend, Error::MaxBalanceOverflow);
+                    MOTION_SPENT.set(&motion_id, &spent);
+                    motion.proposer
+                }
+                // sudo 执行不受 track 限额约束
+                // Calls enacted through sudo are not bound by a track limit
+                None => SUDO_ACCOUNT
+                    .get()
+                    .unwrap_or(None)
+                    .unwrap_or(env().address()),
+            },
+        };
+
+        let spend_id = NEXT_SPEND_ID.get().unwrap_or(0);
+        NEXT_SPEND_ID.set(&(spend_id + 1));
+        SPENDS.set(
+            &spend_id,
+            &Spend {
+                caller,
+                to,
+                amount,
+                payout: false,
+            },
+        );
+        emit(Event::SpendApproved {
+            spend_id,
+            to,
+            amount,
+        });
+        Ok(spend_id)
+    }
+
+    /// 执行已批准的国库支出，从合约余额转出原生代币，任何人可触发
+    #[revive(message, write)]
+    pub fn payout(spend_id: u64) -> Result<(), Error> {
+        let mut spend = SPENDS.get(&spend_id).ok_or(Error::SpendNotFound)?;
+        ensure!(!spend.payout, Error::SpendAlreadyExecuted);
+        ensure!(
+            treasury_free_balance() >= spend.amount,
+            Error::SpendTransferError
+        );
+
+        spend.payout = true;
+        SPENDS.set(&spend_id, &spend);
+        env()
+            .transfer(&spend.to, &spend.amount)
+            .map_err(|_| Error::SpendTransferError)?;
+        emit(Event::SpendPaid {
+            spend_id,
+            to: spend.to,
+            amount: spend.amount,
+        });
+        Ok(())
+    }
+
+    /// 存入原生代币作为分红，按当前治理代币持有量分配给所有持币人
+    #[revive(message, write)]
+    pub fn deposit_rewards() -> Result<(), Error> {
+        let amount = env().value_transferred();
+        ensure!(amount > U256::ZERO, Error::InvalidDeposit);
+        let total = TOTAL_ISSUANCE.get().unwrap_or(U256::ZERO);
+        ensure!(total > U256::ZERO, Error::NoTokenHolders);
+        let per_token = REWARD_PER_TOKEN.get().unwrap_or(U256::ZERO);
+        REWARD_PER_TOKEN.set(&(per_token + amount * U256::from(REWARD_PRECISION) / total));
+        let reserve = REWARD_RESERVE.get().unwrap_or(U256::ZERO);
+        REWARD_RESERVE.set(&(reserve + amount));
+        emit(Event::RewardsDeposited {
+            from: env().caller(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// 地址可领取的分红
+    #[revive(message)]
+    pub fn claimable(owner: Address) -> U256 {
+        PENDING_REWARDS.get(&owner).unwrap_or(U256::ZERO) + unaccrued_rewards(owner)
+    }
+
+    /// 领取分红，返回领取数量
+    #[revive(message, write)]
+    pub fn claim() -> Result<U256, Error> {
+        let caller = env().caller();
+        accrue_rewards(caller);
+        let amount = PENDING_REWARDS.get(&caller).unwrap_or(U256::ZERO);
+        ensure!(amount > U256::ZERO, Error::NoRewards);
+        PENDING_REWARDS.clear(&caller);
+        let reserve = REWARD_RESERVE.get().unwrap_or(U256::ZERO);
+        REWARD_RESERVE.set(&reserve.saturating_sub(amount));
+        env()
+            .transfer(&caller, &amount)
+            .map_err(|_| Error::TransferFailed)?;
+        emit(Event::RewardsClaimed {
+            who: caller,
+            amount,
+        });
+        Ok(amount)
+    }
+
+    /// 未匹配到 message 的调用按治理代币的 ERC-20 Solidity ABI 处理，供钱包与区块浏览器使用
+    #[revive(fallback)]
+    pub fn fallback() {
+        let api = env();
+        let call_data_len = api.call_data_size() as usize;
+        let call_data = api.call_data_copy(0, call_data_len);
+        match erc20::dispatch(&call_data) {
+            Ok(output) => api.return_value(ReturnFlags::empty(), &output),
+            Err(error) => api.return_value(ReturnFlags::REVERT, &Encode::encode(&error)),
+        }
+    }
+
+    #[revive(message)]
+    pub fn storage_version() -> u32 {
+        STORAGE_VERSION.get().unwrap_or(0)
+    }
+
+    /// 分批把存储迁移到当前实现的版本，每次最多处理 count 个旧成员，返回尚未处理的数量，
+    /// 全部处理完才更新存储版本；已是最新版本时不做任何修改，任何人都可以调用
+    #[revive(message, write)]
+    pub fn migrate(count: u32) -> Result<u32, Error> {
+        let from = STORAGE_VERSION.get().unwrap_or(0);
+        if from >= CURRENT_STORAGE_VERSION {
+            return Ok(0);
+        }
+        // v0 为引入存储版本之前的部署，没有余额与发行量检查点
+        // v0 predates the version marker and has no balance or supply checkpoints
+        if from < 2 {
+            // v2：成员列表从单个 Vec 迁移到下标映射，并为每个成员写入当前余额的检查点
+            // v2: move the member Vec into the indexed registry and checkpoint each balance
+            // 旧列表中已没有余额记录的地址不是成员，不再迁移
+            // Entries without a balance record are no longer members and are dropped
+            let legacy = LEGACY_MEMBERS.get().unwrap_or_default();
+            let start = (MIGRATION_CURSOR.get().unwrap_or(0) as usize).min(legacy.len());
+            let end = start.saturating_add(count as usize).min(legacy.len());
+            for member in legacy[start..end].iter() {
+                if MEMBER_BALANCES.get(member).is_some() {
+                    add_member(*member);
+                    checkpoint_balance(*member);
+                }
+            }
+            if end < legacy.len() {
+                MIGRATION_CURSOR.set(&(end as u32));
+                return Ok((legacy.len() - end) as u32);
+            }
+            LEGACY_MEMBERS.set(&Vec::new());
+            MIGRATION_CURSOR.set(&0);
+            checkpoint_supply();
+        }
+        STORAGE_VERSION.set(&CURRENT_STORAGE_VERSION);
+        emit(Event::StorageMigrated {
+            from,
+            to: CURRENT_STORAGE_VERSION,
+        });
+        Ok(0)
     }
 
     fn init_state(
@@ -403,21 +1946,24 @@ pub mod dao {
         sudo_account: Option<Address>,
         track: Option<Track>,
     ) -> Result<(), Error> {
-        let mut members = Vec::new();
         let mut total = U256::ZERO;
         for (user, balance) in users.iter() {
             ensure!(MEMBER_BALANCES.get(user).is_none(), Error::MemberExisted);
+            accrue_rewards(*user);
             MEMBER_BALANCES.set(user, balance);
             MEMBER_LOCK_BALANCES.set(user, &U256::ZERO);
-            members.push(*user);
+            checkpoint_balance(*user);
+            erc20::emit_transfer(Address::zero(), *user, *balance);
+            add_member(*user);
             total = total + *balance;
         }
-        MEMBERS.set(&members);
         PUBLIC_JOIN.set(&public_join);
         SUDO_ACCOUNT.set(&sudo_account);
         TOTAL_ISSUANCE.set(&total);
+        checkpoint_supply();
         TRANSFER_ENABLED.set(&true);
         NEXT_SUDO_CALL_ID.set(&0);
+        STORAGE_VERSION.set(&CURRENT_STORAGE_VERSION);
 
         let token = TokenInfo {
             name: b"WeTEE DAO".to_vec(),
@@ -427,6 +1973,7 @@ pub mod dao {
         TOKENS.set(&0, &token);
 
         if let Some(track) = track {
+            ensure_valid_curves(&track)?;
             TRACKS.set(&0, &track);
             NEXT_TRACK_ID.set(&1);
             DEFAULT_TRACK.set(&Some(0));
@@ -437,11 +1984,180 @@ pub mod dao {
         Ok(())
     }
 
+    fn resolve_track(call: &Call) -> Option<u16> {
+        // 未指定合约的调用作用于 DAO 自身
+        // A call without a contract targets the DAO itself
+        let contract = Some(call.contract.unwrap_or(env().address()));
+        let selector = Some(call.selector);
+        TRACK_RULES
+            .get(&(contract, selector))
+            .or_else(|| TRACK_RULES.get(&(contract, None)))
+            .or_else(|| TRACK_RULES.get(&(None, selector)))
+            .or_else(|| DEFAULT_TRACK.get().unwrap_or(None))
+    }
+
+    fn create_proposal(call: Call, track_id: u16) -> Result<CallId, Error> {
+        let caller = env().caller();
+        ensure!(
+            MEMBER_BALANCES.get(&caller).is_some(),
+            Error::MemberNotExisted
+        );
+        ensure!(TRACKS.get(&track_id).is_some(), Error::NoTrack);
+        let prop_id = NEXT_PROPOSAL_ID.get().unwrap_or(0);
+        NEXT_PROPOSAL_ID.set(&(prop_id + 1));
+        let snapshot = SNAPSHOT_ID.get().unwrap_or(0) + 1;
+        SNAPSHOT_ID.set(&snapshot);
+        let prop = Proposal {
+            caller,
+            call,
+            track_id,
+            status: PropStatus::Pending,
+            submit_block: env().block_number(),
+            snapshot,
+            deposit: U256::ZERO,
+            decide_block: None,
+            confirm_block: None,
+            tally: Tally::default(),
+            executed: false,
+            result: None,
+        };
+        PROPOSALS.set(&prop_id, &prop);
+        emit(Event::ProposalSubmitted {
+            prop_id,
+            caller,
+            track_id,
+        });
+        Ok(prop_id)
+    }
+
+    /// 提案适用的 track，开启快速通道的提案各阶段时长不超过快速通道时长
+    fn proposal_track(prop_id: CallId, track_id: u16) -> Option<Track> {
+        let mut track = TRACKS.get(&track_id)?;
+        if let Some(period) = FAST_TRACKS.get(&prop_id) {
+            track.prepare_period = track.prepare_period.min(period);
+            track.decision_period = track.decision_period.min(period);
+            track.confirm_period = track.confirm_period.min(period);
+            track.min_enactment_period = track.min_enactment_period.min(period);
+        }
+        Some(track)
+    }
+
+    /// 提案调用的哈希，批量提案为整个批量的哈希
+    fn proposal_call_hash(prop_id: CallId, prop: &Proposal) -> [u8; 32] {
+        match PROPOSAL_BATCHES.get(&prop_id) {
+            Some(batch) => batch_hash(&batch),
+            None => call_hash(&prop.call),
+        }
+    }
+
+    /// 调用的哈希只包含合约、选择器、参数与金额，修改 gas 限制或重入标记不能绕过否决；
+    /// 合约为 None 时按 DAO 自身地址计算，与显式写出 DAO 地址的调用相同
+    fn call_hash(call: &Call) -> [u8; 32] {
+        let callee = call.contract.unwrap_or(env().address());
+        keccak256((callee, call.selector, &call.input, call.amount).encode()).0
+    }
+
+    fn batch_hash(batch: &Batch) -> [u8; 32] {
+        let hashes: Vec<[u8; 32]> = batch.calls.iter().map(call_hash).collect();
+        keccak256(hashes.encode()).0
+    }
+
+    fn ensure_not_vetoed(call_hash: [u8; 32]) -> Result<(), Error> {
+        let until = VETOED_CALLS.get(&call_hash).unwrap_or(0);
+        ensure!(env().block_number() >= until, Error::CallVetoed);
+        Ok(())
+    }
+
+    fn ensure_valid_curves(track: &Track) -> Result<(), Error> {
+        ensure!(
+            track.min_approval.is_valid() && track.min_support.is_valid(),
+            Error::InvalidCurve
+        );
+        Ok(())
+    }
+
+    fn ensure_valid_council(council: &Council) -> Result<(), Error> {
+        let size = council.members.len();
+        ensure!(
+            council.threshold > 0 && council.threshold as usize <= size,
+            Error::InvalidCouncil
+        );
+        for (i, member) in council.members.iter().enumerate() {
+            ensure!(
+                !council.members[i + 1..].contains(member),
+                Error::InvalidCouncil
+            );
+        }
+        Ok(())
+    }
+
+    /// 按 (合约, 选择器) → (合约, *) → (*, 选择器) → (*, *) 匹配委员会允许的调用
+    fn council_allows(council: &Council, call: &Call) -> bool {
+        let contract = Some(call.contract.unwrap_or(env().address()));
+        let selector = Some(call.selector);
+        [
+            (contract, selector),
+            (contract, None),
+            (None, selector),
+            (None, None),
+        ]
+        .iter()
+        .any(|key| council.calls.contains(key))
+    }
+
+    /// 动议批准中仍是委员会成员的数量
+    fn approval_count(motion: &Motion, council: &Council) -> u32 {
+        motion
+            .approvals
+            .iter()
+            .filter(|member| council.members.contains(member))
+            .count() as u32
+    }
+
     fn ensure_from_gov() -> Result<(), Error> {
         ensure!(env().caller() == env().address(), Error::MustCallByGov);
         Ok(())
     }
 
+    /// 调用来自紧急委员会正在执行的动议
+    fn ensure_from_emergency() -> Result<(), Error> {
+        ensure_from_gov()?;
+        let council_id = ENACTING_MOTION
+            .get()
+            .unwrap_or(None)
+            .and_then(|motion_id| MOTIONS.get(&motion_id))
+            .map(|motion| motion.council_id);
+        ensure!(
+            council_id.is_some() && council_id == EMERGENCY_COUNCIL.get().unwrap_or(None),
+            Error::MustCallByEmergency
+        );
+        Ok(())
+    }
+
+    fn treasury_free_balance() -> U256 {
+        let reserve = DEPOSIT_RESERVE.get().unwrap_or(U256::ZERO)
+            + REWARD_RESERVE.get().unwrap_or(U256::ZERO);
+        env().balance().saturating_sub(reserve)
+    }
+
+    /// 上次结算以来按当前余额累计的分红
+    fn unaccrued_rewards(owner: Address) -> U256 {
+        let per_token = REWARD_PER_TOKEN.get().unwrap_or(U256::ZERO);
+        let paid = REWARD_PER_TOKEN_PAID.get(&owner).unwrap_or(U256::ZERO);
+        let balance = MEMBER_BALANCES.get(&owner).unwrap_or(U256::ZERO);
+        balance * (per_token - paid) / U256::from(REWARD_PRECISION)
+    }
+
+    /// 余额变动前结算分红，之后的分红按新余额计算
+    fn accrue_rewards(owner: Address) {
+        let earned = unaccrued_rewards(owner);
+        if earned > U256::ZERO {
+            let pending = PENDING_REWARDS.get(&owner).unwrap_or(U256::ZERO);
+            PENDING_REWARDS.set(&owner, &(pending + earned));
+        }
+        REWARD_PER_TOKEN_PAID.set(&owner, &REWARD_PER_TOKEN.get().unwrap_or(U256::ZERO));
+    }
+
     fn free_balance(owner: Address) -> U256 {
         let balance = MEMBER_BALANCES.get(&owner).unwrap_or(U256::ZERO);
         let lock = MEMBER_LOCK_BALANCES.get(&owner).unwrap_or(U256::ZERO);
@@ -452,6 +2168,240 @@ pub mod dao {
         }
     }
 
+    fn checkpoint_balance(owner: Address) {
+        let balance = MEMBER_BALANCES.get(&owner).unwrap_or(U256::ZERO);
+        let count = BALANCE_CHECKPOINT_COUNT.get(&owner).unwrap_or(0);
+        let count = push_checkpoint(
+            count,
+            balance,
+            |i| BALANCE_CHECKPOINTS.get(&(owner, i)),
+            |i, c| BALANCE_CHECKPOINTS.set(&(owner, i), c),
+        );
+        BALANCE_CHECKPOINT_COUNT.set(&owner, &count);
+    }
+
+    fn checkpoint_supply() {
+        let total = TOTAL_ISSUANCE.get().unwrap_or(U256::ZERO);
+        let count = SUPPLY_CHECKPOINT_COUNT.get().unwrap_or(0);
+        let count = push_checkpoint(
+            count,
+            total,
+            |i| SUPPLY_CHECKPOINTS.get(&i),
+            |i, c| SUPPLY_CHECKPOINTS.set(&i, c),
+        );
+        SUPPLY_CHECKPOINT_COUNT.set(&count);
+    }
+
+    /// 追加检查点，同一区块且期间没有新快照时只保留最后的值，返回新的检查点数量
+    fn push_checkpoint(
+        count: u32,
+        balance: U256,
+        get: impl Fn(u32) -> Option<Checkpoint>,
+        set: impl Fn(u32, &Checkpoint),
+    ) -> u32 {
+        let block = env().block_number();
+        let snapshot = SNAPSHOT_ID.get().unwrap_or(0);
+        let checkpoint = Checkpoint {
+            block,
+            snapshot,
+            balance,
+        };
+        if count > 0 && get(count - 1).is_some_and(|c| c.block == block && c.snapshot == snapshot) {
+            set(count - 1, &checkpoint);
+            return count;
+        }
+        set(count, &checkpoint);
+        count + 1
+    }
+
+    /// 提案快照时（提交之前最后写入）的余额
+    fn balance_at_snapshot(owner: Address, snapshot: u32) -> U256 {
+        let count = BALANCE_CHECKPOINT_COUNT.get(&owner).unwrap_or(0);
+        checkpoint_at(
+            count,
+            |c| c.snapshot < snapshot,
+            |i| BALANCE_CHECKPOINTS.get(&(owner, i)),
+        )
+    }
+
+    fn supply_at_snapshot(snapshot: u32) -> U256 {
+        let count = SUPPLY_CHECKPOINT_COUNT.get().unwrap_or(0);
+        checkpoint_at(
+            count,
+            |c| c.snapshot < snapshot,
+            |i| SUPPLY_CHECKPOINTS.get(&i),
+        )
+    }
+
+    /// 二分查找最后一个满足 `before` 的检查点的值，没有则为 0；`before` 须对检查点序列单调
+    fn checkpoint_at(
+        count: u32,
+        before: impl Fn(&Checkpoint) -> bool,
+        get: impl Fn(u32) -> Option<Checkpoint>,
+    ) -> U256 {
+        let (mut lo, mut hi) = (0u32, count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match get(mid) {
+                Some(c) if before(&c) => lo = mid + 1,
+                _ => hi = mid,
+            }
+        }
+        if lo == 0 {
+            return U256::ZERO;
+        }
+        get(lo - 1).map(|c| c.balance).unwrap_or(U256::ZERO)
+    }
+
+    fn emit(event: Event) {
+        env().deposit_event(&event.topics(), &event.encode());
+    }
+
+    /// 多代币接口只操作次级代币，治理代币走 ERC20 接口
+    fn ensure_secondary_token(token_id: u32) -> Result<(), Error> {
+        ensure!(token_id != 0, Error::InvalidTokenId);
+        ensure!(TOKENS.get(&token_id).is_some(), Error::TokenNotFound);
+        Ok(())
+    }
+
+    fn token_transfer_from_to(
+        token_id: u32,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Error> {
+        let from_balance = MEMBER_TOKENS.get(&(from, token_id)).unwrap_or(U256::ZERO);
+        ensure!(from_balance >= amount, Error::LowBalance);
+        MEMBER_TOKENS.set(&(from, token_id), &(from_balance - amount));
+        let to_balance = MEMBER_TOKENS.get(&(to, token_id)).unwrap_or(U256::ZERO);
+        MEMBER_TOKENS.set(&(to, token_id), &(to_balance + amount));
+        emit(Event::TokenTransfer {
+            token_id,
+            from,
+            to,
+            amount,
+        });
+        Ok(())
+    }
+
+    fn set_allowance(owner: Address, spender: Address, value: U256) {
+        ALLOWANCES.set(&(owner, spender), &value);
+        erc20::emit_approval(owner, spender, value);
+        emit(Event::Approval {
+            owner,
+            spender,
+            amount: value,
+        });
+    }
+
+    /// 通过 ecrecover 预编译合约从签名（r ‖ s ‖ v）恢复签名地址，签名无效时返回 None
+    fn ecdsa_recover(hash: &[u8; 32], signature: &[u8; 65]) -> Option<Address> {
+        // 预编译输入：hash ‖ v ‖ r ‖ s，各 32 字节，v 为 27 或 28
+        // Precompile input: hash ‖ v ‖ r ‖ s as 32-byte words, with v being 27 or 28
+        let v = match signature[64] {
+            v @ (0 | 1) => v + 27,
+            v => v,
+        };
+        let mut input = [0u8; 128];
+        input[..32].copy_from_slice(hash);
+        input[63] = v;
+        input[64..].copy_from_slice(&signature[..64]);
+        env()
+            .call(
+                CallFlags::READ_ONLY,
+                &Address::from(ECRECOVER),
+                u64::MAX,
+                u64::MAX,
+                &U256::ZERO,
+                &U256::ZERO,
+                &input,
+                None,
+            )
+            .ok()?;
+        let output = read_return_data();
+        // 签名无效时预编译返回空数据
+        // The precompile returns no data for an invalid signature
+        if output.len() != 32 {
+            return None;
+        }
+        let mut signer = [0u8; 20];
+        signer.copy_from_slice(&output[12..]);
+        Some(Address::from(signer))
+    }
+
+    fn lock_balance(owner: Address, amount: U256) {
+        let lock = MEMBER_LOCK_BALANCES.get(&owner).unwrap_or(U256::ZERO);
+        MEMBER_LOCK_BALANCES.set(&owner, &(lock + amount));
+    }
+
+    fn unlock_balance(owner: Address, amount: U256) {
+        let lock = MEMBER_LOCK_BALANCES.get(&owner).unwrap_or(U256::ZERO);
+        MEMBER_LOCK_BALANCES.set(&owner, &lock.saturating_sub(amount));
+    }
+
+    /// O(1) 追加成员，已存在时忽略
+    fn add_member(member: Address) {
+        if MEMBER_INDEX.get(&member).is_some() {
+            return;
+        }
+        let count = MEMBER_COUNT.get().unwrap_or(0);
+        MEMBER_AT.set(&count, &member);
+        MEMBER_INDEX.set(&member, &count);
+        MEMBER_COUNT.set(&(count + 1));
+    }
+
+    /// O(1) 移除成员：最后一个成员移到被移除成员的下标
+    fn remove_member(member: Address) {
+        let index = match MEMBER_INDEX.get(&member) {
+            Some(index) => index,
+            None => return,
+        };
+        let last = MEMBER_COUNT.get().unwrap_or(0).saturating_sub(1);
+        if let Some(moved) = MEMBER_AT.get(&last).filter(|_| index != last) {
+            MEMBER_AT.set(&index, &moved);
+            MEMBER_INDEX.set(&moved, &index);
+        }
+        MEMBER_AT.clear(&last);
+        MEMBER_INDEX.clear(&member);
+        MEMBER_COUNT.set(&last);
+    }
+
+    fn ensure_transfer_allowed(from: Address, to: Address) -> Result<(), Error> {
+        let frozen = FROZEN_ACCOUNTS.get().unwrap_or_default();
+        ensure!(
+            !frozen.contains(&from) && !frozen.contains(&to),
+            Error::AccountFrozen
+        );
+        let allowlist = TRANSFER_ALLOWLIST.get().unwrap_or_default();
+        if allowlist.contains(&from) || allowlist.contains(&to) {
+            return Ok(());
+        }
+        ensure!(
+            TRANSFER_ENABLED.get().unwrap_or(true),
+            Error::TransferDisable
+        );
+        if TRANSFER_MEMBERS_ONLY.get().unwrap_or(false) {
+            ensure!(
+                MEMBER_BALANCES.get(&to).is_some(),
+                Error::RecipientNotMember
+            );
+        }
+        Ok(())
+    }
+
+    fn update_address_list(list: &Storage<Vec<Address>>, account: Address, included: bool) {
+        let mut accounts = list.get().unwrap_or_default();
+        if accounts.contains(&account) == included {
+            return;
+        }
+        if included {
+            accounts.push(account);
+        } else {
+            accounts.retain(|a| *a != account);
+        }
+        list.set(&accounts);
+    }
+
     fn transfer_from_to(from: Address, to: Address, value: U256) -> Result<(), Error> {
         ensure!(
             MEMBER_BALANCES.get(&from).is_some(),
@@ -460,20 +2410,365 @@ pub mod dao {
         let free = free_balance(from);
         ensure!(free >= value, Error::LowBalance);
         let from_balance = MEMBER_BALANCES.get(&from).unwrap_or(U256::ZERO);
+        accrue_rewards(from);
         MEMBER_BALANCES.set(&from, &(from_balance - value));
         let to_balance = MEMBER_BALANCES.get(&to).unwrap_or(U256::ZERO);
+        accrue_rewards(to);
         MEMBER_BALANCES.set(&to, &(to_balance + value));
-        let mut members = MEMBERS.get().unwrap_or_default();
-        if !members.iter().any(|x| *x == to) {
+        checkpoint_balance(from);
+        checkpoint_balance(to);
+        erc20::emit_transfer(from, to, value);
+        emit(Event::Transfer {
+            from,
+            to,
+            amount: value,
+        });
+        if MEMBER_INDEX.get(&to).is_none() {
             // 初始化新成员的锁仓余额，保持与 join()/public_join() 状态一致
             // Initialize lock balance for new member to keep state consistent with join()/public_join()
             MEMBER_LOCK_BALANCES.set(&to, &U256::ZERO);
-            members.push(to);
-            MEMBERS.set(&members);
+            add_member(to);
+            emit(Event::MemberJoined {
+                member: to,
+                balance: value,
+            });
+        }
+        Ok(())
+    }
+
+    /// 提案状态机：Ongoing → Confirming → Approved，决策期结束仍未通过则 Rejected。
+    /// 确认期内一旦不再满足通过条件，则退回 Ongoing（决策期已过则直接 Rejected）。
+    /// 通过后加入执行队列，等待 min_enactment_period 后执行；离开决策期时释放 track 决策名额。
+    fn refresh_status(prop_id: CallId, prop: &mut Proposal, track: &Track) {
+        let was_deciding = is_deciding(&prop.status);
+        let old_status = prop.status.clone();
+        advance_status(prop_id, prop, track);
+        if prop.status != old_status {
+            emit(Event::ProposalStatusChanged {
+                prop_id,
+                status: prop.status.clone(),
+            });
+        }
+        if was_deciding && !is_deciding(&prop.status) {
+            release_deciding(prop.track_id, track);
+        }
+    }
+
+    fn advance_status(prop_id: CallId, prop: &mut Proposal, track: &Track) {
+        let now = env().block_number();
+        let decide_block = match prop.decide_block {
+            Some(b) => b,
+            None => return,
+        };
+        let decide_end = decide_block.saturating_add(track.decision_period);
+
+        if prop.status == PropStatus::Ongoing {
+            if is_passing(prop, track) {
+                prop.status = PropStatus::Confirming;
+                prop.confirm_block = Some(now);
+            } else if now >= decide_end {
+                prop.status = PropStatus::Rejected(now);
+                return;
+            }
+        }
+
+        if prop.status == PropStatus::Confirming {
+            if !is_passing(prop, track) {
+                prop.confirm_block = None;
+                prop.status = if now >= decide_end {
+                    PropStatus::Rejected(now)
+                } else {
+                    PropStatus::Ongoing
+                };
+                return;
+            }
+            let confirm_block = prop.confirm_block.unwrap_or(now);
+            if now >= confirm_block.saturating_add(track.confirm_period) {
+                prop.status = PropStatus::Approved(now);
+                let mut queue = ENACTMENT_QUEUE.get().unwrap_or_default();
+                queue.push(prop_id);
+                ENACTMENT_QUEUE.set(&queue);
+            }
+        }
+    }
+
+    /// 提案在结束前被移出：释放决策名额，或从排队中移除
+    fn leave_decision(prop_id: CallId, prop: &Proposal) -> Result<(), Error> {
+        if is_deciding(&prop.status) {
+            let track = TRACKS.get(&prop.track_id).ok_or(Error::NoTrack)?;
+            release_deciding(prop.track_id, &track);
+            return Ok(());
+        }
+        ensure!(
+            prop.status == PropStatus::Pending,
+            Error::InvalidProposalStatus
+        );
+        let mut queue = DECIDING_QUEUE.get(&prop.track_id).unwrap_or_default();
+        if queue.contains(&prop_id) {
+            queue.retain(|id| *id != prop_id);
+            DECIDING_QUEUE.set(&prop.track_id, &queue);
         }
         Ok(())
     }
 
+    /// 清空提案押金并从保留余额中扣除，返回押金数额
+    fn take_deposit(prop: &mut Proposal) -> U256 {
+        let deposit = prop.deposit;
+        prop.deposit = U256::ZERO;
+        let reserve = DEPOSIT_RESERVE.get().unwrap_or(U256::ZERO);
+        DEPOSIT_RESERVE.set(&reserve.saturating_sub(deposit));
+        deposit
+    }
+
+    fn release_deciding(track_id: u16, track: &Track) {
+        let deciding = DECIDING_COUNT.get(&track_id).unwrap_or(0);
+        DECIDING_COUNT.set(&track_id, &deciding.saturating_sub(1));
+        promote_queued(track_id, track);
+    }
+
+    /// 按排队顺序把提案提升到决策期，直到 track 决策名额用完
+    fn promote_queued(track_id: u16, track: &Track) {
+        let mut queue = DECIDING_QUEUE.get(&track_id).unwrap_or_default();
+        let mut deciding = DECIDING_COUNT.get(&track_id).unwrap_or(0);
+        let now = env().block_number();
+        let mut promoted = 0;
+        while deciding < track.max_deciding && promoted < queue.len() {
+            let prop_id = queue[promoted];
+            promoted += 1;
+            if let Some(mut prop) = PROPOSALS.get(&prop_id) {
+                prop.status = PropStatus::Ongoing;
+                prop.decide_block = Some(now);
+                PROPOSALS.set(&prop_id, &prop);
+                emit(Event::ProposalStatusChanged {
+                    prop_id,
+                    status: prop.status.clone(),
+                });
+                deciding += 1;
+            }
+        }
+        if promoted > 0 {
+            queue.drain(..promoted);
+            DECIDING_QUEUE.set(&track_id, &queue);
+            DECIDING_COUNT.set(&track_id, &deciding);
+        }
+    }
+
+    fn enactment_block(prop_id: CallId, prop: &Proposal) -> Option<BlockNumber> {
+        let approved = match prop.status {
+            PropStatus::Approved(b) => b,
+            _ => return None,
+        };
+        let track = proposal_track(prop_id, prop.track_id)?;
+        Some(approved.saturating_add(track.min_enactment_period))
+    }
+
+    /// 默认 conviction：0.1x 不额外锁定，1x 到 6x 依次锁定 1、2、4、8、16、32 个周期，周期为决策期长度
+    fn conviction_of(track_id: u16, track: &Track) -> Conviction {
+        if let Some(conviction) = TRACK_CONVICTIONS.get(&track_id) {
+            return conviction;
+        }
+        let mut levels = Vec::new();
+        levels.push(ConvictionLevel {
+            multiplier: 1000,
+            lock_periods: 0,
+        });
+        for i in 0..6 {
+            levels.push(ConvictionLevel {
+                multiplier: 10000 * (i + 1),
+                lock_periods: 1 << i,
+            });
+        }
+        Conviction {
+            lock_period: track.decision_period,
+            levels,
+        }
+    }
+
+    /// 撤销一个委托并从受托人的委托人列表中移除，最后一个委托撤销后释放锁定
+    fn clear_delegation(owner: Address, delegation: &Delegation) {
+        let track_id = delegation.track_id;
+        DELEGATIONS.clear(&(owner, track_id));
+        let mut delegators = DELEGATORS
+            .get(&(delegation.to, track_id))
+            .unwrap_or_default();
+        delegators.retain(|a| *a != owner);
+        DELEGATORS.set(&(delegation.to, track_id), &delegators);
+        let mut scopes = DELEGATION_SCOPES.get(&owner).unwrap_or_default();
+        scopes.retain(|s| *s != track_id);
+        DELEGATION_SCOPES.set(&owner, &scopes);
+        if scopes.is_empty() {
+            let amount = DELEGATED_BALANCE.get(&owner).unwrap_or(U256::ZERO);
+            unlock_balance(owner, amount);
+            DELEGATED_BALANCE.clear(&owner);
+        }
+        emit(Event::Undelegated {
+            from: owner,
+            track_id,
+        });
+    }
+
+    /// 成员离开或被删除时撤销其全部委托以及委托给他的委托，不受投票锁定期限制
+    fn clear_delegations(owner: Address) {
+        for scope in DELEGATION_SCOPES
+            .get(&owner)
+            .unwrap_or_default()
+            .into_iter()
+        {
+            if let Some(delegation) = DELEGATIONS.get(&(owner, scope)) {
+                clear_delegation(owner, &delegation);
+            }
+        }
+        let scopes = core::iter::once(None).chain((0..NEXT_TRACK_ID.get().unwrap_or(0)).map(Some));
+        for scope in scopes {
+            for from in DELEGATORS
+                .get(&(owner, scope))
+                .unwrap_or_default()
+                .into_iter()
+            {
+                if let Some(delegation) = DELEGATIONS.get(&(from, scope)) {
+                    clear_delegation(from, &delegation);
+                }
+            }
+        }
+    }
+
+    /// 成员在 track 上生效的委托：单 track 委托优先于全局委托
+    fn effective_delegation(owner: Address, track_id: u16) -> Option<Delegation> {
+        DELEGATIONS
+            .get(&(owner, Some(track_id)))
+            .or_else(|| DELEGATIONS.get(&(owner, None)))
+    }
+
+    /// 成员在 track（None 为任意 track）上是否已委托他人
+    fn is_delegating(owner: Address, track_id: Option<u16>) -> bool {
+        match track_id {
+            Some(track_id) => effective_delegation(owner, track_id).is_some(),
+            None => !DELEGATION_SCOPES.get(&owner).unwrap_or_default().is_empty(),
+        }
+    }
+
+    /// 成员在 track（None 为任意 track）上是否收到了委托；全局委托在每个 track 上都计入
+    fn has_delegators(owner: Address, track_id: Option<u16>) -> bool {
+        let received = |scope: Option<u16>| {
+            !DELEGATORS
+                .get(&(owner, scope))
+                .unwrap_or_default()
+                .is_empty()
+        };
+        match track_id {
+            Some(track_id) => received(None) || received(Some(track_id)),
+            None => {
+                received(None) || (0..NEXT_TRACK_ID.get().unwrap_or(0)).any(|id| received(Some(id)))
+            }
+        }
+    }
+
+    /// 汇总直接委托给 `to` 的权重；`lock_until` 为 Some 时同时延长这些委托的锁定期。
+    /// `snapshot` 为 (提案, 快照编号) 时每个委托人按快照余额封顶，已亲自投票的委托人不计入。
+    fn delegated_power(
+        to: Address,
+        track_id: u16,
+        lock_until: Option<BlockNumber>,
+        snapshot: Option<(CallId, u32)>,
+    ) -> U256 {
+        let mut power = U256::ZERO;
+        let mut delegators = DELEGATORS.get(&(to, Some(track_id))).unwrap_or_default();
+        // 全局委托人若在该 track 上另有委托，则不计入
+        // Global delegators with a track-specific delegation are counted there instead
+        for from in DELEGATORS.get(&(to, None)).unwrap_or_default().into_iter() {
+            if DELEGATIONS.get(&(from, Some(track_id))).is_none() {
+                delegators.push(from);
+            }
+        }
+        for from in delegators.into_iter() {
+            let mut delegation = match effective_delegation(from, track_id) {
+                Some(d) => d,
+                None => continue,
+            };
+            // 已亲自投票的委托人不再通过受托人计入
+            // Delegators who voted themselves are not counted through the delegate
+            let voted = snapshot
+                .is_some_and(|(prop_id, _)| MEMBER_PROPOSAL_VOTE.get(&(from, prop_id)).is_some());
+            if voted {
+                continue;
+            }
+            let until = lock_until.unwrap_or(0);
+            if until > delegation.locked_until {
+                delegation.locked_until = until;
+                DELEGATIONS.set(&(from, delegation.track_id), &delegation);
+            }
+            power += match snapshot {
+                Some((_, snapshot)) => delegation.amount.min(balance_at_snapshot(from, snapshot)),
+                None => delegation.amount,
+            };
+        }
+        power
+    }
+
+    fn is_deciding(status: &PropStatus) -> bool {
+        matches!(status, PropStatus::Ongoing | PropStatus::Confirming)
+    }
+
+    fn add_to_tally(tally: &mut Tally, vote: &VoteInfo) {
+        match vote.opinion {
+            Opinion::YES => tally.ayes += vote.vote_weight,
+            Opinion::NO => tally.nays += vote.vote_weight,
+        }
+        tally.support += vote.pledge + vote.delegated;
+    }
+
+    fn remove_from_tally(tally: &mut Tally, vote: &VoteInfo) {
+        match vote.opinion {
+            Opinion::YES => tally.ayes = tally.ayes.saturating_sub(vote.vote_weight),
+            Opinion::NO => tally.nays = tally.nays.saturating_sub(vote.vote_weight),
+        }
+        tally.support = tally.support.saturating_sub(vote.pledge + vote.delegated);
+    }
+
+    fn is_passing(prop: &Proposal, track: &Track) -> bool {
+        tally_info(prop, track).passing
+    }
+
+    /// 按决策期已过区块数评估曲线，赞成率 = ayes / (ayes + nays)，支持率 = support / 提交时的总发行量
+    fn tally_info(prop: &Proposal, track: &Track) -> TallyInfo {
+        let now = env().block_number();
+        let elapsed = prop
+            .decide_block
+            .map(|b| now.saturating_sub(b))
+            .unwrap_or(0);
+        let total_issuance = supply_at_snapshot(prop.snapshot);
+        let approval = to_bps(prop.tally.ayes, prop.tally.ayes + prop.tally.nays);
+        let support = to_bps(prop.tally.support, total_issuance);
+        let min_approval = track.min_approval.y(elapsed);
+        let min_support = track.min_support.y(elapsed);
+
+        TallyInfo {
+            tally: prop.tally.clone(),
+            total_issuance,
+            elapsed,
+            approval,
+            support,
+            min_approval,
+            min_support,
+            // 无赞成票时不通过，避免阈值曲线降到 0 后空提案被通过
+            // Never pass without aye votes, even if the curves have decayed to zero
+            passing: !prop.tally.ayes.is_zero()
+                && approval >= min_approval
+                && support >= min_support,
+        }
+    }
+
+    fn to_bps(part: U256, whole: U256) -> u32 {
+        if whole.is_zero() {
+            return 0;
+        }
+        let bps = part.saturating_mul(U256::from(10000u64)) / whole;
+        if bps >= U256::from(10000u64) {
+            return 10000;
+        }
+        u32::try_from(bps).unwrap_or(10000)
+    }
+
     fn exec_call_internal(call: Call) -> Result<Vec<u8>, Error> {
         let call_flags = if call.allow_reentry {
             CallFlags::ALLOW_REENTRY
@@ -493,11 +2788,99 @@ pub mod dao {
                 None,
             )
             .map_err(|_| Error::CallFailed)?;
+        Ok(read_return_data())
+    }
+
+    /// 执行调用并记录结果，失败时不回滚本合约状态
+    fn exec_call_with_result(call: Call) -> CallResult {
+        let block = env().block_number();
+        match exec_call_internal(call) {
+            Ok(output) => CallResult {
+                block,
+                success: true,
+                output,
+            },
+            // 失败时不回滚本合约状态，记录 revert 数据以便链下排查
+            // Keep our own state on failure and record the revert data for inspection
+            Err(_) => CallResult {
+                block,
+                success: false,
+                output: read_return_data(),
+            },
+        }
+    }
+
+    /// 按顺序执行批量调用。AllOrNothing 模式遇到失败即返回错误，
+    /// 由消息返回 Err 回滚此前已执行的调用。
+    fn exec_batch(batch: &Batch) -> Result<Vec<CallResult>, Error> {
+        let mut results = Vec::with_capacity(batch.calls.len());
+        for call in batch.calls.iter() {
+            let result = exec_call_with_result(call.clone());
+            ensure!(
+                result.success || batch.mode == BatchMode::BestEffort,
+                Error::CallFailed
+            );
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    fn record_sudo_call(caller: Address, call: Call, result: CallResult) {
+        let call_id = NEXT_SUDO_CALL_ID.get().unwrap_or(0);
+        NEXT_SUDO_CALL_ID.set(&(call_id + 1));
+        let success = result.success;
+        SUDO_CALLS.set(
+            &call_id,
+            &SudoCall {
+                call,
+                caller,
+                result,
+            },
+        );
+        emit(Event::SudoExecuted {
+            call_id,
+            caller,
+            success,
+        });
+    }
+
+    fn read_return_data() -> Vec<u8> {
         let size = env().return_data_size() as usize;
         let mut buf = alloc::vec![0u8; size];
         let mut slice = buf.as_mut_slice();
         env().return_data_copy(&mut slice, 0);
-        Ok(buf)
+        buf
+    }
+
+    /// 从 start（默认最新）开始按编号倒序分页，跳过已删除的编号
+    fn page_desc<K, T>(
+        total: K,
+        start: Option<K>,
+        size: u32,
+        get: impl Fn(K) -> Option<T>,
+    ) -> Vec<(K, T)>
+    where
+        K: Copy + PartialOrd + From<u8> + core::ops::Sub<Output = K>,
+    {
+        let (zero, one) = (K::from(0), K::from(1));
+        let mut out = Vec::new();
+        if total == zero || size == 0 {
+            return out;
+        }
+        let mut cur = start.unwrap_or(total - one);
+        if cur >= total {
+            cur = total - one;
+        }
+        for _ in 0..size {
+            if let Some(item) = get(cur) {
+                out.push((cur, item));
+            }
+            if cur == zero {
+                break;
+            }
+            cur = cur - one;
+        }
+        out
     }
 
     fn encode_raw_call(selector: &Selector, input: &[u8]) -> Vec<u8> {
@@ -2,7 +2,11 @@ use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use wrevive_api::BlockNumber;
 
+use crate::{Error, ensure};
+
 const FIXED_ONE: i128 = 1_000_000;
+/// 阈值上限：10000 即 100%
+const MAX_THRESHOLD: u32 = 10_000;
 
 fn fixed_from_i64(v: i64) -> i128 {
     (v as i128) * FIXED_ONE
@@ -26,6 +30,10 @@ pub struct Percent {
 }
 
 impl Percent {
+    pub fn is_valid(&self) -> bool {
+        self.v <= MAX_THRESHOLD
+    }
+
     pub fn mul_i64(&self, value: i64) -> i64 {
         ((value as i128) * (self.v as i128) / 10_000) as i64
     }
@@ -51,6 +59,31 @@ pub enum Curve {
 }
 
 impl Curve {
+    /// 阈值不超过 100% 且随区块不增；Reciprocal 的 x_scale、x_offset 须为正，避免除零
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Curve::LinearDecreasing { begin, end, .. }
+            | Curve::SteppedDecreasing { begin, end, .. } => {
+                *begin <= MAX_THRESHOLD && end <= begin
+            }
+            Curve::Reciprocal {
+                factor,
+                x_scale,
+                x_offset,
+                y_offset,
+            } => {
+                if *x_scale == 0 || *x_offset <= 0 {
+                    return false;
+                }
+                // 曲线单调递减，只需检查 x = 0 处的值
+                // The curve is decreasing, so checking y(0) bounds every point
+                (*factor as i64 / *x_offset)
+                    .checked_sub(*y_offset)
+                    .is_some_and(|y| y <= MAX_THRESHOLD as i64)
+            }
+        }
+    }
+
     pub fn y(&self, x: BlockNumber) -> u32 {
         match self {
             Curve::LinearDecreasing { begin, end, length } => {
@@ -71,7 +104,7 @@ impl Curve {
                     return *begin;
                 }
                 let num_steps = x / *period;
-                let sub_value = num_steps * *step;
+                let sub_value = num_steps.saturating_mul(*step);
                 if sub_value >= *begin || begin.saturating_sub(sub_value) <= *end {
                     return *end;
                 }
@@ -94,8 +127,8 @@ impl Curve {
     }
 }
 
-pub fn arg_to_curve(arg: CurveArg) -> Curve {
-    match arg {
+pub fn arg_to_curve(arg: CurveArg) -> Result<Curve, Error> {
+    let curve = match arg {
         CurveArg::LinearDecreasing { begin, end, length } => {
             Curve::LinearDecreasing { begin, end, length }
         }
@@ -116,6 +149,10 @@ pub fn arg_to_curve(arg: CurveArg) -> Curve {
             x_offset_percent,
             x_scale_arg,
         } => {
+            ensure!(
+                begin <= MAX_THRESHOLD && end <= begin && x_offset_percent.is_valid(),
+                Error::InvalidCurve
+            );
             let x_scale = if x_scale_arg == 0 { 1 } else { x_scale_arg };
             let mut slot = begin - end;
             let mut x_offset: i64 = 0;
@@ -123,6 +160,7 @@ pub fn arg_to_curve(arg: CurveArg) -> Curve {
 
             if x_offset_percent.v > 0 {
                 let x = x_offset_percent.mul_i64(slot as i64);
+                ensure!(x > 0, Error::InvalidCurve);
                 let y = fixed_from_i64(slot as i64)
                     / (fixed_from_u64(x as u64) + fixed_from_i64(x_offset));
                 let ratio = if y == 0 { 1 } else { slot / u32_from_fixed(y).max(1) };
@@ -139,5 +177,7 @@ pub fn arg_to_curve(arg: CurveArg) -> Curve {
                 y_offset,
             }
         }
-    }
+    };
+    ensure!(curve.is_valid(), Error::InvalidCurve);
+    Ok(curve)
 }
@@ -22,6 +22,10 @@ pub struct VoteInfo {
     pub pledge: U256,
     pub opinion: Opinion,
     pub vote_weight: U256,
+    /// 投票时计入的委托代币数量
+    pub delegated: U256,
+    /// 所选 conviction 等级
+    pub conviction: u8,
     pub unlock_block: BlockNumber,
     pub call_id: CallId,
     pub calller: Address,
@@ -29,6 +33,40 @@ pub struct VoteInfo {
     pub deleted: bool,
 }
 
+/// conviction 等级：multiplier 为基点（10000 = 1x），锁定 lock_periods 个锁定周期
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct ConvictionLevel {
+    pub multiplier: u32,
+    pub lock_periods: BlockNumber,
+}
+
+/// track 的 conviction 配置，vote 的 conviction 参数为 levels 的下标
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Conviction {
+    pub lock_period: BlockNumber,
+    pub levels: Vec<ConvictionLevel>,
+}
+
+/// 余额检查点：自 block 起余额为 balance
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Checkpoint {
+    pub block: BlockNumber,
+    /// 写入时的快照编号，每次提交提案递增
+    pub snapshot: u32,
+    pub balance: U256,
+}
+
+/// 投票权委托，track_id 为 None 时对所有 track 生效（单 track 委托优先）
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Delegation {
+    pub to: Address,
+    pub track_id: Option<u16>,
+    pub amount: U256,
+    pub block: BlockNumber,
+    /// 计入该委托权重的投票中最晚的解锁区块，此前不可撤销委托
+    pub locked_until: BlockNumber,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub struct Track {
     pub name: Vec<u8>,
@@ -51,10 +89,16 @@ pub enum PropStatus {
     Approved(BlockNumber),
     Rejected(BlockNumber),
     Canceled,
+    /// 被治理终止，押金没收进国库
+    Killed,
+    /// 通过后在执行前被紧急委员会否决
+    Vetoed(BlockNumber),
 }
 
 pub type CallId = u32;
 pub type Selector = [u8; 4];
+/// track 规则键：(合约, 选择器)，None 表示通配
+pub type TrackRuleKey = (Option<Address>, Option<Selector>);
 
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub struct Call {
@@ -66,6 +110,104 @@ pub struct Call {
     pub allow_reentry: bool,
 }
 
+#[derive(Clone, Default, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Tally {
+    pub ayes: U256,
+    pub nays: U256,
+    pub support: U256,
+}
+
+/// 提案当前计票结果与曲线阈值，比例均为基点（10000 = 100%）
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct TallyInfo {
+    pub tally: Tally,
+    pub total_issuance: U256,
+    /// 进入决策期后经过的区块数
+    pub elapsed: BlockNumber,
+    pub approval: u32,
+    pub support: u32,
+    pub min_approval: u32,
+    pub min_support: u32,
+    pub passing: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Proposal {
+    pub caller: Address,
+    pub call: Call,
+    pub track_id: u16,
+    pub status: PropStatus,
+    pub submit_block: BlockNumber,
+    /// 投票权快照编号，提交之后（含同一区块内）的余额变动不计入
+    pub snapshot: u32,
+    pub deposit: U256,
+    /// 进入决策期的区块
+    pub decide_block: Option<BlockNumber>,
+    /// 进入确认期的区块
+    pub confirm_block: Option<BlockNumber>,
+    pub tally: Tally,
+    pub executed: bool,
+    /// 执行结果，执行失败也会记录
+    pub result: Option<CallResult>,
+}
+
+/// 提案或 sudo 调用的执行结果
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct CallResult {
+    pub block: BlockNumber,
+    pub success: bool,
+    pub output: Vec<u8>,
+}
+
+/// sudo 调用记录
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct SudoCall {
+    pub call: Call,
+    pub caller: Address,
+    pub result: CallResult,
+}
+
+/// 批量调用模式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum BatchMode {
+    /// 任一调用失败则整体回滚
+    AllOrNothing,
+    /// 逐个执行，失败的调用记录结果后继续
+    BestEffort,
+}
+
+/// 批量调用，按顺序执行
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Batch {
+    pub calls: Vec<Call>,
+    pub mode: BatchMode,
+}
+
+/// 治理设立的委员会（如技术委员会、财务、紧急委员会），
+/// threshold 个成员批准后以 DAO 身份执行 calls 允许的调用
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Council {
+    pub name: Vec<u8>,
+    pub members: Vec<Address>,
+    pub threshold: u32,
+    /// 允许执行的 (合约, 选择器)，匹配规则同 track 规则，(None, None) 允许任意调用
+    pub calls: Vec<TrackRuleKey>,
+    /// 单个动议执行期间可批准的支出总额上限
+    pub max_spend: U256,
+}
+
+/// 委员会动议，批准数达到委员会 threshold 后可执行
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Motion {
+    pub council_id: u16,
+    pub call: Call,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+    pub executed: bool,
+    /// 执行结果，执行失败也会记录
+    pub result: Option<CallResult>,
+}
+
 #[derive(Clone)]
 pub struct CallInput<'a>(pub &'a [u8]);
 
@@ -75,6 +217,41 @@ impl Encode for CallInput<'_> {
     }
 }
 
+/// 治理代币转账策略，冻结优先于白名单，白名单不受开关与仅成员接收限制
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct TransferPolicy {
+    pub enabled: bool,
+    /// 只有成员可以接收转账
+    pub members_only: bool,
+    pub allowlist: Vec<Address>,
+    pub frozen: Vec<Address>,
+}
+
+/// 线性释放计划：start + cliff 之前不释放，之后按区块线性释放，到 start + duration 全部释放
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Vesting {
+    pub amount: U256,
+    /// 已领取（已解锁）的数量
+    pub claimed: U256,
+    pub start: BlockNumber,
+    pub cliff: BlockNumber,
+    pub duration: BlockNumber,
+}
+
+impl Vesting {
+    /// 截至 now 已释放的总量（含已领取部分）
+    pub fn vested(&self, now: BlockNumber) -> U256 {
+        if now < self.start.saturating_add(self.cliff) {
+            return U256::ZERO;
+        }
+        let elapsed = now - self.start;
+        if elapsed >= self.duration {
+            return self.amount;
+        }
+        self.amount * U256::from(u64::from(elapsed)) / U256::from(u64::from(self.duration))
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub struct Spend {
     pub caller: Address,
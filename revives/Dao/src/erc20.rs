@@ -0,0 +1,221 @@
+//! 治理代币（token 0）的 ERC-20 Solidity ABI 接口。
+//! 将 ABI 调用映射到已有的 SCALE message，并发出带 indexed topic 的 Transfer / Approval 日志。
+//! 另提供 EIP-2612 permit 所需的 EIP-712 签名哈希。
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloy_core::primitives::{Address as SolAddress, B256, U256 as SolU256};
+use alloy_core::sol;
+use alloy_core::sol_types::{Eip712Domain, SolCall, SolEvent, SolStruct};
+use wrevive_api::{Address, Decode, Encode, Env, U256, Vec, env};
+
+use crate::{Error, dao, ensure};
+
+sol! {
+    function totalSupply() external view returns (uint256);
+    function balanceOf(address owner) external view returns (uint256);
+    function allowance(address owner, address spender) external view returns (uint256);
+    function transfer(address to, uint256 amount) external returns (bool);
+    function approve(address spender, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+    function decimals() external view returns (uint8);
+    function name() external view returns (string);
+    function symbol() external view returns (string);
+    function nonces(address owner) external view returns (uint256);
+    function DOMAIN_SEPARATOR() external view returns (bytes32);
+    function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s) external;
+
+    struct Permit {
+        address owner;
+        address spender;
+        uint256 value;
+        uint256 nonce;
+        uint256 deadline;
+    }
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+}
+
+/// 处理 ABI 编码的调用数据，返回 ABI 编码的返回值；空调用数据视为原生代币转入
+pub(crate) fn dispatch(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.is_empty() {
+        return Ok(Vec::new());
+    }
+    ensure!(input.len() >= 4, Error::InvalidCallData);
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&input[..4]);
+
+    let out = match selector {
+        totalSupplyCall::SELECTOR => {
+            totalSupplyCall::abi_encode_returns(&to_sol_u256(dao::total_supply()))
+        }
+        balanceOfCall::SELECTOR => {
+            let call = balanceOfCall::abi_decode(input).map_err(|_| Error::InvalidCallData)?;
+            let balance = dao::balance_of(from_sol_address(call.owner));
+            balanceOfCall::abi_encode_returns(&to_sol_u256(balance))
+        }
+        allowanceCall::SELECTOR => {
+            let call = allowanceCall::abi_decode(input).map_err(|_| Error::InvalidCallData)?;
+            let allowance =
+                dao::allowance(from_sol_address(call.owner), from_sol_address(call.spender));
+            allowanceCall::abi_encode_returns(&to_sol_u256(allowance))
+        }
+        transferCall::SELECTOR => {
+            let call = transferCall::abi_decode(input).map_err(|_| Error::InvalidCallData)?;
+            dao::transfer(from_sol_address(call.to), from_sol_u256(call.amount)?)?;
+            transferCall::abi_encode_returns(&true)
+        }
+        approveCall::SELECTOR => {
+            let call = approveCall::abi_decode(input).map_err(|_| Error::InvalidCallData)?;
+            dao::approve(from_sol_address(call.spender), from_sol_u256(call.amount)?)?;
+            approveCall::abi_encode_returns(&true)
+        }
+        transferFromCall::SELECTOR => {
+            let call = transferFromCall::abi_decode(input).map_err(|_| Error::InvalidCallData)?;
+            dao::transfer_from(
+                from_sol_address(call.from),
+                from_sol_address(call.to),
+                from_sol_u256(call.amount)?,
+            )?;
+            transferFromCall::abi_encode_returns(&true)
+        }
+        decimalsCall::SELECTOR => {
+            let token = dao::token(0).ok_or(Error::TokenNotFound)?;
+            decimalsCall::abi_encode_returns(&token.decimals)
+        }
+        nameCall::SELECTOR => {
+            let token = dao::token(0).ok_or(Error::TokenNotFound)?;
+            nameCall::abi_encode_returns(&String::from_utf8_lossy(&token.name).into_owned())
+        }
+        symbolCall::SELECTOR => {
+            let token = dao::token(0).ok_or(Error::TokenNotFound)?;
+            symbolCall::abi_encode_returns(&String::from_utf8_lossy(&token.symbol).into_owned())
+        }
+        noncesCall::SELECTOR => {
+            let call = noncesCall::abi_decode(input).map_err(|_| Error::InvalidCallData)?;
+            let nonce = dao::nonces(from_sol_address(call.owner));
+            noncesCall::abi_encode_returns(&SolU256::from(nonce))
+        }
+        DOMAIN_SEPARATORCall::SELECTOR => {
+            DOMAIN_SEPARATORCall::abi_encode_returns(&B256::from(dao::domain_separator()))
+        }
+        permitCall::SELECTOR => {
+            let call = permitCall::abi_decode(input).map_err(|_| Error::InvalidCallData)?;
+            // 超出 u64 的期限视为永不过期
+            // A deadline beyond u64 never expires
+            let deadline = u64::try_from(call.deadline).unwrap_or(u64::MAX);
+            let mut signature = [0u8; 65];
+            signature[..32].copy_from_slice(call.r.as_slice());
+            signature[32..64].copy_from_slice(call.s.as_slice());
+            signature[64] = call.v;
+            dao::permit(
+                from_sol_address(call.owner),
+                from_sol_address(call.spender),
+                from_sol_u256(call.value)?,
+                deadline,
+                signature,
+            )?;
+            permitCall::abi_encode_returns(&permitReturn {})
+        }
+        _ => return Err(Error::InvalidCallData),
+    };
+    Ok(out)
+}
+
+/// 发出 ERC-20 Transfer 日志，铸造 / 销毁使用零地址
+pub(crate) fn emit_transfer(from: Address, to: Address, value: U256) {
+    emit_log(&Transfer {
+        from: to_sol_address(from),
+        to: to_sol_address(to),
+        value: to_sol_u256(value),
+    });
+}
+
+pub(crate) fn emit_approval(owner: Address, spender: Address, value: U256) {
+    emit_log(&Approval {
+        owner: to_sol_address(owner),
+        spender: to_sol_address(spender),
+        value: to_sol_u256(value),
+    });
+}
+
+/// EIP-712 域：代币名、版本 "1"、链 ID 与本合约地址
+pub(crate) fn permit_domain() -> Eip712Domain {
+    let name = dao::token(0)
+        .map(|token| String::from_utf8_lossy(&token.name).into_owned())
+        .unwrap_or_default();
+    Eip712Domain::new(
+        Some(Cow::Owned(name)),
+        Some(Cow::Borrowed("1")),
+        Some(chain_id()),
+        Some(to_sol_address(env().address())),
+        None,
+    )
+}
+
+/// 运行时的链 ID，防止签名在其他链上重放
+#[cfg(not(test))]
+fn chain_id() -> SolU256 {
+    use pallet_revive_uapi::{HostFn, HostFnImpl};
+    let mut output = [0u8; 32];
+    HostFnImpl::chain_id(&mut output);
+    SolU256::from_le_bytes(output)
+}
+
+/// 链下测试环境没有链 ID，固定为该值
+#[cfg(test)]
+pub(crate) const OFF_CHAIN_CHAIN_ID: u64 = 420420420;
+
+#[cfg(test)]
+fn chain_id() -> SolU256 {
+    SolU256::from(OFF_CHAIN_CHAIN_ID)
+}
+
+/// 签名在 deadline（含）之前有效
+pub(crate) fn ensure_permit_live(now: u64, deadline: u64) -> Result<(), Error> {
+    ensure!(now <= deadline, Error::PermitExpired);
+    Ok(())
+}
+
+/// owner 对 Permit 结构签名的 EIP-712 哈希
+pub(crate) fn permit_hash(
+    owner: Address,
+    spender: Address,
+    value: U256,
+    nonce: u64,
+    deadline: u64,
+) -> [u8; 32] {
+    Permit {
+        owner: to_sol_address(owner),
+        spender: to_sol_address(spender),
+        value: to_sol_u256(value),
+        nonce: SolU256::from(nonce),
+        deadline: SolU256::from(deadline),
+    }
+    .eip712_signing_hash(&permit_domain())
+    .0
+}
+
+fn emit_log<E: SolEvent>(event: &E) {
+    let topics: Vec<[u8; 32]> = event.encode_topics().into_iter().map(|t| t.0.0).collect();
+    env().deposit_event(&topics, &event.encode_data());
+}
+
+fn to_sol_address(address: Address) -> SolAddress {
+    SolAddress::from(address.0)
+}
+
+fn from_sol_address(address: SolAddress) -> Address {
+    Address::from(address.into_array())
+}
+
+// U256 的 SCALE 编码为 32 字节小端序，借此与 alloy 的 U256 互转
+// U256 SCALE-encodes as 32 little-endian bytes, which bridges it to alloy's U256
+fn to_sol_u256(value: U256) -> SolU256 {
+    SolU256::from_le_slice(&value.encode())
+}
+
+fn from_sol_u256(value: SolU256) -> Result<U256, Error> {
+    U256::decode(&mut &value.to_le_bytes::<32>()[..]).map_err(|_| Error::InvalidCallData)
+}
@@ -37,4 +37,36 @@ pub enum Error {
     SpendAlreadyExecuted,
     SpendTransferError,
     ReentrantCall,
+    InvalidEnactmentTime,
+    TrackRuleNotFound,
+    InvalidDelegate,
+    DelegationExisted,
+    DelegationNotFound,
+    DelegationTooDeep,
+    DelegationLocked,
+    InvalidConviction,
+    InvalidTokenId,
+    InvalidCallData,
+    InvalidBatch,
+    AccountFrozen,
+    RecipientNotMember,
+    InvalidVesting,
+    NoVestedBalance,
+    InvalidCurve,
+    NoTokenHolders,
+    NoRewards,
+    InvalidCouncil,
+    CouncilNotFound,
+    NotCouncilMember,
+    CallNotAllowed,
+    MotionNotFound,
+    MotionAlreadyApproved,
+    MotionNotApproved,
+    MotionExecuted,
+    MustCallByEmergency,
+    InvalidFastTrack,
+    CallVetoed,
+    PermitExpired,
+    InvalidSignature,
+    VoteDelegated,
 }
@@ -0,0 +1,276 @@
+use alloy_core::primitives::keccak256;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use wrevive_api::{Address, BlockNumber, U256, Vec};
+
+use crate::datas::{CallId, Opinion, PropStatus, Selector, TokenInfo, TransferPolicy};
+
+/// 合约事件，SCALE 编码后作为事件数据发出
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum Event {
+    MemberJoined {
+        member: Address,
+        balance: U256,
+    },
+    MemberLeft {
+        member: Address,
+        burned: U256,
+    },
+    MemberDeleted {
+        member: Address,
+        burned: U256,
+    },
+    PublicJoinSet {
+        public_join: bool,
+    },
+    Transfer {
+        from: Address,
+        to: Address,
+        amount: U256,
+    },
+    Approval {
+        owner: Address,
+        spender: Address,
+        amount: U256,
+    },
+    Burn {
+        from: Address,
+        amount: U256,
+    },
+    SudoExecuted {
+        call_id: u32,
+        caller: Address,
+        success: bool,
+    },
+    SudoRemoved {
+        caller: Address,
+    },
+    TrackAdded {
+        track_id: u16,
+    },
+    TrackEdited {
+        track_id: u16,
+    },
+    DefaultTrackSet {
+        track_id: u16,
+    },
+    TrackRuleSet {
+        contract: Option<Address>,
+        selector: Option<Selector>,
+        track_id: u16,
+    },
+    TrackRuleRemoved {
+        contract: Option<Address>,
+        selector: Option<Selector>,
+    },
+    TrackConvictionSet {
+        track_id: u16,
+    },
+    TokenCreated {
+        token_id: u32,
+        info: TokenInfo,
+    },
+    TokenMinted {
+        token_id: u32,
+        to: Address,
+        amount: U256,
+    },
+    TokenBurned {
+        token_id: u32,
+        from: Address,
+        amount: U256,
+    },
+    TokenTransfer {
+        token_id: u32,
+        from: Address,
+        to: Address,
+        amount: U256,
+    },
+    TokenApproval {
+        token_id: u32,
+        owner: Address,
+        spender: Address,
+        amount: U256,
+    },
+    ProposalSubmitted {
+        prop_id: CallId,
+        caller: Address,
+        track_id: u16,
+    },
+    ProposalDeposited {
+        prop_id: CallId,
+        amount: U256,
+    },
+    ProposalStatusChanged {
+        prop_id: CallId,
+        status: PropStatus,
+    },
+    ProposalExecuted {
+        prop_id: CallId,
+        success: bool,
+    },
+    DepositRefunded {
+        prop_id: CallId,
+        to: Address,
+        amount: U256,
+    },
+    Voted {
+        vote_id: u64,
+        prop_id: CallId,
+        voter: Address,
+        opinion: Opinion,
+        vote_weight: U256,
+    },
+    VoteCanceled {
+        vote_id: u64,
+        voter: Address,
+    },
+    VoteUnlocked {
+        vote_id: u64,
+        voter: Address,
+        amount: U256,
+    },
+    Delegated {
+        from: Address,
+        to: Address,
+        track_id: Option<u16>,
+        amount: U256,
+    },
+    Undelegated {
+        from: Address,
+        track_id: Option<u16>,
+    },
+    SpendApproved {
+        spend_id: u64,
+        to: Address,
+        amount: U256,
+    },
+    SpendPaid {
+        spend_id: u64,
+        to: Address,
+        amount: U256,
+    },
+    StorageMigrated {
+        from: u32,
+        to: u32,
+    },
+    TransferPolicyChanged {
+        policy: TransferPolicy,
+    },
+    VestingGranted {
+        to: Address,
+        amount: U256,
+        start: BlockNumber,
+        cliff: BlockNumber,
+        duration: BlockNumber,
+    },
+    VestingClaimed {
+        who: Address,
+        amount: U256,
+    },
+    RewardsDeposited {
+        from: Address,
+        amount: U256,
+    },
+    RewardsClaimed {
+        who: Address,
+        amount: U256,
+    },
+    CouncilSet {
+        council_id: u16,
+    },
+    MotionProposed {
+        motion_id: u32,
+        council_id: u16,
+        proposer: Address,
+    },
+    MotionApproved {
+        motion_id: u32,
+        member: Address,
+    },
+    MotionExecuted {
+        motion_id: u32,
+        success: bool,
+    },
+    EmergencyCouncilSet {
+        council_id: Option<u16>,
+        cooldown: BlockNumber,
+    },
+    ProposalVetoed {
+        prop_id: CallId,
+        call_hash: [u8; 32],
+        until: BlockNumber,
+    },
+    ProposalFastTracked {
+        prop_id: CallId,
+        period: BlockNumber,
+    },
+}
+
+impl Event {
+    /// topic[0] 为事件名的 keccak256，其后为事件涉及的地址（左补零到 32 字节）
+    pub fn topics(&self) -> Vec<[u8; 32]> {
+        let (name, addresses): (&str, Vec<Address>) = match self {
+            Event::MemberJoined { member, .. } => ("MemberJoined", [*member].into()),
+            Event::MemberLeft { member, .. } => ("MemberLeft", [*member].into()),
+            Event::MemberDeleted { member, .. } => ("MemberDeleted", [*member].into()),
+            Event::PublicJoinSet { .. } => ("PublicJoinSet", Vec::new()),
+            Event::Transfer { from, to, .. } => ("Transfer", [*from, *to].into()),
+            Event::Approval { owner, spender, .. } => ("Approval", [*owner, *spender].into()),
+            Event::Burn { from, .. } => ("Burn", [*from].into()),
+            Event::SudoExecuted { caller, .. } => ("SudoExecuted", [*caller].into()),
+            Event::SudoRemoved { caller } => ("SudoRemoved", [*caller].into()),
+            Event::TrackAdded { .. } => ("TrackAdded", Vec::new()),
+            Event::TrackEdited { .. } => ("TrackEdited", Vec::new()),
+            Event::DefaultTrackSet { .. } => ("DefaultTrackSet", Vec::new()),
+            Event::TrackRuleSet { contract, .. } => {
+                ("TrackRuleSet", contract.iter().copied().collect())
+            }
+            Event::TrackRuleRemoved { contract, .. } => {
+                ("TrackRuleRemoved", contract.iter().copied().collect())
+            }
+            Event::TrackConvictionSet { .. } => ("TrackConvictionSet", Vec::new()),
+            Event::TokenCreated { .. } => ("TokenCreated", Vec::new()),
+            Event::TokenMinted { to, .. } => ("TokenMinted", [*to].into()),
+            Event::TokenBurned { from, .. } => ("TokenBurned", [*from].into()),
+            Event::TokenTransfer { from, to, .. } => ("TokenTransfer", [*from, *to].into()),
+            Event::TokenApproval { owner, spender, .. } => {
+                ("TokenApproval", [*owner, *spender].into())
+            }
+            Event::ProposalSubmitted { caller, .. } => ("ProposalSubmitted", [*caller].into()),
+            Event::ProposalDeposited { .. } => ("ProposalDeposited", Vec::new()),
+            Event::ProposalStatusChanged { .. } => ("ProposalStatusChanged", Vec::new()),
+            Event::ProposalExecuted { .. } => ("ProposalExecuted", Vec::new()),
+            Event::DepositRefunded { to, .. } => ("DepositRefunded", [*to].into()),
+            Event::Voted { voter, .. } => ("Voted", [*voter].into()),
+            Event::VoteCanceled { voter, .. } => ("VoteCanceled", [*voter].into()),
+            Event::VoteUnlocked { voter, .. } => ("VoteUnlocked", [*voter].into()),
+            Event::Delegated { from, to, .. } => ("Delegated", [*from, *to].into()),
+            Event::Undelegated { from, .. } => ("Undelegated", [*from].into()),
+            Event::SpendApproved { to, .. } => ("SpendApproved", [*to].into()),
+            Event::SpendPaid { to, .. } => ("SpendPaid", [*to].into()),
+            Event::StorageMigrated { .. } => ("StorageMigrated", Vec::new()),
+            Event::TransferPolicyChanged { .. } => ("TransferPolicyChanged", Vec::new()),
+            Event::VestingGranted { to, .. } => ("VestingGranted", [*to].into()),
+            Event::VestingClaimed { who, .. } => ("VestingClaimed", [*who].into()),
+            Event::RewardsDeposited { from, .. } => ("RewardsDeposited", [*from].into()),
+            Event::RewardsClaimed { who, .. } => ("RewardsClaimed", [*who].into()),
+            Event::CouncilSet { .. } => ("CouncilSet", Vec::new()),
+            Event::MotionProposed { proposer, .. } => ("MotionProposed", [*proposer].into()),
+            Event::MotionApproved { member, .. } => ("MotionApproved", [*member].into()),
+            Event::MotionExecuted { .. } => ("MotionExecuted", Vec::new()),
+            Event::EmergencyCouncilSet { .. } => ("EmergencyCouncilSet", Vec::new()),
+            Event::ProposalVetoed { .. } => ("ProposalVetoed", Vec::new()),
+            Event::ProposalFastTracked { .. } => ("ProposalFastTracked", Vec::new()),
+        };
+
+        let mut topics = Vec::with_capacity(1 + addresses.len());
+        topics.push(keccak256(name.as_bytes()).0);
+        for address in addresses.into_iter() {
+            let mut topic = [0u8; 32];
+            topic[12..].copy_from_slice(&address.0);
+            topics.push(topic);
+        }
+        topics
+    }
+}
@@ -98,6 +98,61 @@ fn transfer_to_non_member_auto_adds() {
     assert!(dao::list().iter().any(|x| *x == charlie));
 }
 
+#[test]
+fn transfer_policy_gates_transfers() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+    let charlie = Address::from([3u8; 20]);
+    assert_eq!(dao::set_transfer_enabled(false), Err(Error::MustCallByGov));
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::set_transfer_enabled(false), Ok(()));
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(
+        dao::transfer(bob(), U256::from(1u64)),
+        Err(Error::TransferDisable)
+    );
+
+    // 白名单地址在转账关闭时仍可接收
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::set_transfer_allowed(bob(), true), Ok(()));
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::transfer(bob(), U256::from(1u64)), Ok(()));
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::set_transfer_enabled(true), Ok(()));
+    assert_eq!(dao::set_transfer_members_only(true), Ok(()));
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(
+        dao::transfer(charlie, U256::from(1u64)),
+        Err(Error::RecipientNotMember)
+    );
+
+    // 冻结优先于白名单
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::set_frozen(bob(), true), Ok(()));
+    assert_eq!(dao::set_frozen(bob(), true), Ok(()));
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(
+        dao::transfer(bob(), U256::from(1u64)),
+        Err(Error::AccountFrozen)
+    );
+    assert_eq!(
+        dao::transfer_policy(),
+        TransferPolicy {
+            enabled: true,
+            members_only: true,
+            allowlist: vec![bob()],
+            frozen: vec![bob()],
+        }
+    );
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::set_frozen(bob(), false), Ok(()));
+    assert_eq!(dao::transfer_policy().frozen, vec![]);
+}
+
 #[test]
 fn approve_and_transfer_from() {
     setup();
@@ -164,6 +219,88 @@ fn sudo_account_workflow() {
     assert_eq!(dao::sudo_account(), None);
 }
 
+#[test]
+fn sudo_calls_are_recorded() {
+    setup();
+    let sudo = Address::from(gov());
+    let _ = dao::new_with_default_track(vec![], true, Some(sudo));
+    assert_eq!(dao::sudo_calls(None, 10), vec![]);
+
+    with_engine(|e| e.set_caller(gov()));
+    let mut calls = Vec::new();
+    for i in 0..3u8 {
+        let call = Call {
+            contract: None,
+            selector: [i; 4],
+            input: vec![],
+            amount: U256::ZERO,
+            ref_time_limit: u64::MAX,
+            allow_reentry: false,
+        };
+        let result = dao::sudo(call.clone()).unwrap();
+        calls.push((call, result));
+    }
+
+    let record = dao::sudo_call(1).unwrap();
+    assert_eq!(record.call, calls[1].0);
+    assert_eq!(record.caller, sudo);
+    assert_eq!(record.result, calls[1].1);
+    assert_eq!(dao::sudo_call(3), None);
+
+    let ids: Vec<CallId> = dao::sudo_calls(None, 2)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(ids, vec![2, 1]);
+    let ids: Vec<CallId> = dao::sudo_calls(Some(0), 2)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(ids, vec![0]);
+
+    // 移除 sudo 后历史仍可查询
+    assert_eq!(dao::remove_sudo(), Ok(()));
+    assert_eq!(dao::sudo_calls(None, 10).len(), 3);
+}
+
+#[test]
+fn sudo_batch_records_each_call() {
+    setup();
+    let sudo = Address::from(gov());
+    let _ = dao::new_with_default_track(vec![], true, Some(sudo));
+
+    with_engine(|e| e.set_caller(gov()));
+    let empty = Batch {
+        calls: vec![],
+        mode: BatchMode::BestEffort,
+    };
+    assert_eq!(dao::sudo_batch(empty), Err(Error::InvalidBatch));
+
+    let calls = vec![
+        empty_call(),
+        Call {
+            selector: [1u8; 4],
+            ..empty_call()
+        },
+    ];
+    let batch = Batch {
+        calls: calls.clone(),
+        mode: BatchMode::BestEffort,
+    };
+    let results = dao::sudo_batch(batch).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(dao::sudo_call(0).unwrap().call, calls[0]);
+    assert_eq!(dao::sudo_call(1).unwrap().call, calls[1]);
+    assert_eq!(dao::sudo_call(1).unwrap().result, results[1]);
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    let batch = Batch {
+        calls,
+        mode: BatchMode::AllOrNothing,
+    };
+    assert_eq!(dao::sudo_batch(batch), Err(Error::MustCallByGov));
+}
+
 #[test]
 fn sudo_by_non_sudo_fails() {
     setup();
@@ -182,6 +319,95 @@ fn sudo_by_non_sudo_fails() {
     assert_eq!(dao::sudo(call), Err(Error::MustCallByGov));
 }
 
+fn council_of(members: Vec<Address>, threshold: u32) -> Council {
+    Council {
+        name: b"technical".to_vec(),
+        members,
+        threshold,
+        calls: vec![(None, Some([1u8; 4]))],
+        max_spend: U256::ZERO,
+    }
+}
+
+#[test]
+fn council_config_managed_by_gov() {
+    setup();
+    let _ = dao::new_with_default_track(vec![], true, Some(Address::from(gov())));
+    let council = council_of(vec![alice(), bob()], 2);
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::add_council(council.clone()), Err(Error::MustCallByGov));
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(
+        dao::add_council(council_of(vec![alice(), bob()], 0)),
+        Err(Error::InvalidCouncil)
+    );
+    assert_eq!(
+        dao::add_council(council_of(vec![alice(), bob()], 3)),
+        Err(Error::InvalidCouncil)
+    );
+    assert_eq!(
+        dao::add_council(council_of(vec![alice(), alice()], 1)),
+        Err(Error::InvalidCouncil)
+    );
+    assert_eq!(dao::add_council(council.clone()), Ok(0));
+    assert_eq!(dao::council(0), Some(council));
+    assert!(dao::is_council_member(0, bob()));
+
+    assert_eq!(
+        dao::edit_council(1, council_of(vec![alice()], 1)),
+        Err(Error::CouncilNotFound)
+    );
+    assert_eq!(dao::edit_council(0, council_of(vec![alice()], 1)), Ok(()));
+    assert!(!dao::is_council_member(0, bob()));
+    assert_eq!(dao::council_list().len(), 1);
+}
+
+#[test]
+fn council_motion_needs_threshold_approvals() {
+    setup();
+    let _ = dao::new_with_default_track(vec![], true, Some(Address::from(gov())));
+    let carol = Address::from([3u8; 20]);
+    with_engine(|e| e.set_caller(e.current_contract));
+    let _ = dao::add_council(council_of(vec![alice(), bob(), carol], 2));
+
+    let mut call = empty_call();
+    call.selector = [1u8; 4];
+    let mut other = empty_call();
+    other.selector = [2u8; 4];
+
+    with_engine(|e| e.set_caller([9u8; 20]));
+    assert_eq!(
+        dao::propose_motion(0, call.clone()),
+        Err(Error::NotCouncilMember)
+    );
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::propose_motion(0, other), Err(Error::CallNotAllowed));
+    assert_eq!(dao::propose_motion(0, call.clone()), Ok(0));
+    assert_eq!(dao::motion(0).unwrap().approvals, vec![alice()]);
+    assert_eq!(dao::approve_motion(0), Err(Error::MotionAlreadyApproved));
+    assert_eq!(dao::exec_motion(0), Err(Error::MotionNotApproved));
+
+    // 被移出委员会的成员批准不再计数
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::approve_motion(0), Ok(2));
+    with_engine(|e| e.set_caller(e.current_contract));
+    let _ = dao::edit_council(0, council_of(vec![alice(), carol], 2));
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::exec_motion(0), Err(Error::MotionNotApproved));
+
+    with_engine(|e| e.set_caller([3u8; 20]));
+    assert_eq!(dao::approve_motion(0), Ok(2));
+    let result = dao::exec_motion(0).unwrap();
+    let motion = dao::motion(0).unwrap();
+    assert!(motion.executed);
+    assert_eq!(motion.result, Some(result));
+    assert_eq!(dao::exec_motion(0), Err(Error::MotionExecuted));
+    assert_eq!(dao::approve_motion(0), Err(Error::MotionExecuted));
+    assert_eq!(dao::motions(None, 10).len(), 1);
+}
+
 #[test]
 fn track_management() {
     setup();
@@ -257,6 +483,88 @@ fn set_track_rule_and_lookup() {
     let contract = Some(Address::from([9u8; 20]));
     let selector = Some([0x12u8, 0x34u8, 0x56u8, 0x78u8]);
     assert_eq!(dao::set_track_rule(contract, selector, tid), Ok(()));
+    assert_eq!(dao::track_rules(), vec![((contract, selector), tid)]);
+}
+
+#[test]
+fn track_rule_resolution_precedence() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    let by_pair = dao::add_track(instant_track()).unwrap();
+    let by_contract = dao::add_track(instant_track()).unwrap();
+    let by_selector = dao::add_track(instant_track()).unwrap();
+    let target = Address::from([9u8; 20]);
+    let selector = [0x12u8, 0x34u8, 0x56u8, 0x78u8];
+    let call = Call {
+        contract: Some(target),
+        selector,
+        ..empty_call()
+    };
+
+    assert_eq!(dao::track_for_call(call.clone()), Some(0));
+    let _ = dao::set_track_rule(None, Some(selector), by_selector);
+    assert_eq!(dao::track_for_call(call.clone()), Some(by_selector));
+    let _ = dao::set_track_rule(Some(target), None, by_contract);
+    assert_eq!(dao::track_for_call(call.clone()), Some(by_contract));
+    let _ = dao::set_track_rule(Some(target), Some(selector), by_pair);
+    assert_eq!(dao::track_for_call(call.clone()), Some(by_pair));
+    assert_eq!(dao::track_rules().len(), 3);
+
+    assert_eq!(dao::remove_track_rule(Some(target), Some(selector)), Ok(()));
+    assert_eq!(dao::track_for_call(call.clone()), Some(by_contract));
+    assert_eq!(
+        dao::remove_track_rule(Some(target), Some(selector)),
+        Err(Error::TrackRuleNotFound)
+    );
+    assert_eq!(dao::track_rules().len(), 2);
+
+    // 提案使用解析出的 track，而不是由提案人指定
+    with_engine(|e| e.set_caller([1u8; 20]));
+    let prop_id = dao::submit_proposal(call).unwrap();
+    assert_eq!(dao::proposal(prop_id).unwrap().track_id, by_contract);
+}
+
+#[test]
+fn batch_proposal_requires_single_track() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    let strict = dao::add_track(voting_track()).unwrap();
+    let target = Address::from([9u8; 20]);
+    let _ = dao::set_track_rule(Some(target), None, strict);
+    let restricted = Call {
+        contract: Some(target),
+        ..empty_call()
+    };
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    let empty = Batch {
+        calls: vec![],
+        mode: BatchMode::AllOrNothing,
+    };
+    assert_eq!(dao::submit_batch_proposal(empty), Err(Error::InvalidBatch));
+    // 批量中混入更严格 track 的调用，不能借默认 track 通过
+    let mixed = Batch {
+        calls: vec![empty_call(), restricted.clone()],
+        mode: BatchMode::AllOrNothing,
+    };
+    assert_eq!(dao::submit_batch_proposal(mixed), Err(Error::InvalidBatch));
+
+    let batch = Batch {
+        calls: vec![restricted.clone(), restricted.clone()],
+        mode: BatchMode::BestEffort,
+    };
+    let prop_id = dao::submit_batch_proposal(batch.clone()).unwrap();
+    let prop = dao::proposal(prop_id).unwrap();
+    assert_eq!(prop.track_id, strict);
+    assert_eq!(prop.call, restricted);
+    assert_eq!(dao::proposal_batch(prop_id), Some(batch));
+    assert_eq!(dao::proposal_batch(prop_id + 1), None);
 }
 
 #[test]
@@ -292,3 +600,1876 @@ fn delete_by_gov() {
     assert_eq!(dao::balance_of(alice()), U256::ZERO);
     assert_eq!(dao::total_supply(), U256::ZERO);
 }
+
+fn instant_track() -> Track {
+    Track {
+        name: b"instant".to_vec(),
+        prepare_period: 0,
+        max_deciding: 10,
+        confirm_period: 0,
+        decision_period: 0,
+        min_enactment_period: 0,
+        decision_deposit: U256::from(1u64),
+        max_balance: U256::from(1000u64),
+        min_approval: Curve::LinearDecreasing {
+            begin: 10000,
+            end: 5000,
+            length: 30,
+        },
+        min_support: Curve::LinearDecreasing {
+            begin: 10000,
+            end: 50,
+            length: 30,
+        },
+    }
+}
+
+fn empty_call() -> Call {
+    Call {
+        contract: None,
+        selector: [0u8; 4],
+        input: vec![],
+        amount: U256::ZERO,
+        ref_time_limit: u64::MAX,
+        allow_reentry: false,
+    }
+}
+
+#[test]
+fn submit_and_deposit_proposal() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    let prop_id = dao::submit_proposal(empty_call()).unwrap();
+    assert_eq!(prop_id, 0);
+    assert_eq!(dao::proposal(0).unwrap().status, PropStatus::Pending);
+
+    // 押金不足
+    assert_eq!(dao::deposit_proposal(prop_id), Err(Error::InvalidDeposit));
+
+    with_engine(|e| e.value_transferred = U256::from(1u64));
+    assert_eq!(dao::deposit_proposal(prop_id), Ok(()));
+    let prop = dao::proposal(prop_id).unwrap();
+    assert_eq!(prop.status, PropStatus::Ongoing);
+    assert_eq!(prop.deposit, U256::from(1u64));
+    assert_eq!(prop.decide_block, Some(0));
+
+    assert_eq!(
+        dao::deposit_proposal(prop_id),
+        Err(Error::InvalidProposalStatus)
+    );
+}
+
+#[test]
+fn deposit_before_prepare_period_fails() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+
+    with_engine(|e| {
+        e.set_caller([1u8; 20]);
+        e.value_transferred = U256::from(1u64);
+    });
+    let prop_id = dao::submit_proposal(empty_call()).unwrap();
+    // 默认 track 的 prepare_period 为 1，链下区块高度恒为 0
+    assert_eq!(
+        dao::deposit_proposal(prop_id),
+        Err(Error::InvalidDepositTime)
+    );
+}
+
+#[test]
+fn submit_proposal_requires_member_and_track() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(
+        dao::submit_proposal(empty_call()),
+        Err(Error::MemberNotExisted)
+    );
+}
+
+#[test]
+fn submit_proposal_without_track_fails() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new(users, true, None);
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::submit_proposal(empty_call()), Err(Error::NoTrack));
+}
+
+#[test]
+fn proposal_without_votes_is_rejected() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| {
+        e.set_caller([1u8; 20]);
+        e.value_transferred = U256::from(1u64);
+    });
+    let prop_id = dao::submit_proposal(empty_call()).unwrap();
+    assert_eq!(dao::deposit_proposal(prop_id), Ok(()));
+    assert_eq!(dao::confirm_proposal(prop_id), Ok(PropStatus::Rejected(0)));
+    assert_eq!(
+        dao::exec_proposal(prop_id),
+        Err(Error::ProposalNotConfirmed)
+    );
+}
+
+#[test]
+fn proposals_paged_newest_first() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    for _ in 0..3 {
+        dao::submit_proposal(empty_call()).unwrap();
+    }
+    let ids: Vec<CallId> = dao::proposals(None, 2)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(ids, vec![2, 1]);
+    let ids: Vec<CallId> = dao::proposals(Some(0), 5)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(ids, vec![0]);
+}
+
+fn voting_track() -> Track {
+    Track {
+        decision_period: 10,
+        ..instant_track()
+    }
+}
+
+fn open_proposal() -> CallId {
+    let deposit = dao::track(0).unwrap().decision_deposit;
+    with_engine(|e| {
+        e.set_caller([1u8; 20]);
+        e.value_transferred = deposit;
+    });
+    let prop_id = dao::submit_proposal(empty_call()).unwrap();
+    assert_eq!(dao::deposit_proposal(prop_id), Ok(()));
+    with_engine(|e| e.value_transferred = U256::ZERO);
+    prop_id
+}
+
+#[test]
+fn vote_locks_pledge_until_cancel() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let prop_id = open_proposal();
+
+    let vote_id = dao::vote(prop_id, Opinion::NO, U256::from(40u64), 1).unwrap();
+    assert_eq!(dao::lock_balance_of(alice()), U256::from(40u64));
+    assert_eq!(
+        dao::proposal(prop_id).unwrap().tally.nays,
+        U256::from(40u64)
+    );
+    // 锁定部分不可转账
+    assert_eq!(
+        dao::transfer(bob(), U256::from(70u64)),
+        Err(Error::LowBalance)
+    );
+    assert_eq!(
+        dao::vote(prop_id, Opinion::YES, U256::from(10u64), 1),
+        Err(Error::InvalidVote)
+    );
+    assert_eq!(dao::unlock(vote_id), Err(Error::ProposalInDecision));
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::cancel_vote(vote_id), Err(Error::InvalidVoteUser));
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::cancel_vote(vote_id), Ok(()));
+    assert_eq!(dao::lock_balance_of(alice()), U256::ZERO);
+    assert_eq!(dao::proposal(prop_id).unwrap().tally, Tally::default());
+    assert_eq!(dao::unlock(vote_id), Err(Error::VoteAlreadyUnlocked));
+
+    let votes = dao::votes_of(alice(), None, 10);
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].0, vote_id);
+    assert!(votes[0].1.deleted);
+}
+
+#[test]
+fn vote_after_decision_period_fails() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+    let prop_id = open_proposal();
+
+    // instant track 的决策期为 0，存入押金后即已结束
+    assert_eq!(
+        dao::vote(prop_id, Opinion::YES, U256::from(10u64), 1),
+        Err(Error::InvalidVoteTime)
+    );
+}
+
+#[test]
+fn unlock_waits_for_unlock_block() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let prop_id = open_proposal();
+
+    assert_eq!(
+        dao::vote(prop_id, Opinion::YES, U256::from(200u64), 1),
+        Err(Error::LowBalance)
+    );
+    let vote_id = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    // 全部发行量投赞成且确认期为 0，投票后立即通过
+    assert_eq!(
+        dao::proposal(prop_id).unwrap().status,
+        PropStatus::Approved(0)
+    );
+    assert_eq!(dao::cancel_vote(vote_id), Err(Error::InvalidVoteStatus));
+    // 1x conviction 在决策期终点 10 后再锁定一个周期，链下区块高度恒为 0
+    assert_eq!(dao::vote_info(vote_id).unwrap().unlock_block, 20);
+    assert_eq!(dao::unlock(vote_id), Err(Error::InvalidVoteUnlockTime));
+    assert_eq!(dao::lock_balance_of(alice()), U256::from(100u64));
+}
+
+#[test]
+fn tally_reports_approval_and_support() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let prop_id = open_proposal();
+
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    let info = dao::proposal_tally(prop_id).unwrap();
+    assert_eq!(info.total_issuance, U256::from(150u64));
+    assert_eq!(info.elapsed, 0);
+    assert_eq!(info.approval, 10000);
+    assert_eq!(info.support, 6666);
+    // 决策期起点曲线要求 100% 支持率
+    assert_eq!(info.min_approval, 10000);
+    assert_eq!(info.min_support, 10000);
+    assert!(!info.passing);
+    assert_eq!(dao::proposal(prop_id).unwrap().status, PropStatus::Ongoing);
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    let _ = dao::vote(prop_id, Opinion::NO, U256::from(50u64), 1).unwrap();
+    let info = dao::proposal_tally(prop_id).unwrap();
+    assert_eq!(info.approval, 6666);
+    assert_eq!(info.support, 10000);
+    assert!(!info.passing);
+    assert_eq!(dao::proposal_tally(9), None);
+}
+
+#[test]
+fn passing_proposal_waits_in_confirming() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let track = Track {
+        confirm_period: 5,
+        ..voting_track()
+    };
+    let _ = dao::new_with_track(users, true, None, track);
+    let prop_id = open_proposal();
+
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    assert!(dao::proposal_tally(prop_id).unwrap().passing);
+    let prop = dao::proposal(prop_id).unwrap();
+    assert_eq!(prop.status, PropStatus::Confirming);
+    assert_eq!(prop.confirm_block, Some(0));
+    assert_eq!(dao::confirm_proposal(prop_id), Ok(PropStatus::Confirming));
+    assert_eq!(
+        dao::exec_proposal(prop_id),
+        Err(Error::ProposalNotConfirmed)
+    );
+}
+
+#[test]
+fn spend_requires_gov_and_lists_newest_first() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(
+        dao::spend(bob(), U256::from(10u64)),
+        Err(Error::MustCallByGov)
+    );
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::spend(bob(), U256::from(10u64)), Ok(0));
+    assert_eq!(dao::spend(alice(), U256::from(20u64)), Ok(1));
+
+    let spend = dao::get_spend(0).unwrap();
+    assert_eq!(spend.to, bob());
+    assert_eq!(spend.amount, U256::from(10u64));
+    assert!(!spend.payout);
+
+    let page = dao::spends(None, 10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page[0].0, 1);
+    assert_eq!(page[1].0, 0);
+    assert_eq!(dao::spends(Some(0), 10).len(), 1);
+}
+
+#[test]
+fn spends_in_one_enactment_share_track_limit() {
+    const ENACTING_PROPOSAL: Storage<Option<CallId>> = storage!(b"enacting_proposal");
+
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+    let prop_id = open_proposal();
+
+    // 模拟提案执行期间的多笔支出（如批量提案）
+    ENACTING_PROPOSAL.set(&Some(prop_id));
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(
+        dao::spend(bob(), U256::from(1001u64)),
+        Err(Error::MaxBalanceOverflow)
+    );
+    assert_eq!(dao::spend(bob(), U256::from(600u64)), Ok(0));
+    assert_eq!(
+        dao::spend(bob(), U256::from(600u64)),
+        Err(Error::MaxBalanceOverflow)
+    );
+    assert_eq!(dao::spend(bob(), U256::from(400u64)), Ok(1));
+    assert_eq!(dao::get_spend(1).unwrap().caller, alice());
+}
+
+#[test]
+fn payout_checks_spend_state_and_balance() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    let spend_id = dao::spend(bob(), U256::from(10u64)).unwrap();
+    let empty_id = dao::spend(bob(), U256::ZERO).unwrap();
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::payout(9), Err(Error::SpendNotFound));
+    // 链下合约余额为 0，国库不足
+    assert_eq!(dao::payout(spend_id), Err(Error::SpendTransferError));
+    assert!(!dao::get_spend(spend_id).unwrap().payout);
+
+    assert_eq!(dao::payout(empty_id), Ok(()));
+    assert!(dao::get_spend(empty_id).unwrap().payout);
+    assert_eq!(dao::payout(empty_id), Err(Error::SpendAlreadyExecuted));
+}
+
+#[test]
+fn enactment_waits_for_min_enactment_period() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let track = Track {
+        min_enactment_period: 5,
+        ..voting_track()
+    };
+    let _ = dao::new_with_track(users, true, None, track);
+    let prop_id = open_proposal();
+
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    assert_eq!(
+        dao::proposal(prop_id).unwrap().status,
+        PropStatus::Approved(0)
+    );
+    assert_eq!(dao::enactment_queue(), vec![(prop_id, 5)]);
+    // 链下区块高度恒为 0，未到可执行区块
+    assert_eq!(
+        dao::exec_proposal(prop_id),
+        Err(Error::InvalidEnactmentTime)
+    );
+    assert!(!dao::proposal(prop_id).unwrap().executed);
+}
+
+#[test]
+fn exec_proposal_records_result() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let prop_id = open_proposal();
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    assert_eq!(dao::enactment_queue(), vec![(prop_id, 0)]);
+
+    let result = dao::exec_proposal(prop_id).unwrap();
+    assert_eq!(result.block, 0);
+    let prop = dao::proposal(prop_id).unwrap();
+    assert!(prop.executed);
+    assert_eq!(prop.result, Some(result));
+    assert!(dao::enactment_queue().is_empty());
+    assert_eq!(
+        dao::exec_proposal(prop_id),
+        Err(Error::InvalidProposalStatus)
+    );
+}
+
+#[test]
+fn failed_batch_rolls_back_earlier_calls() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let dao_addr = Address::from(with_engine(|e| e.current_contract));
+
+    with_engine(|e| e.register_contract(dao_addr, call));
+    let spend_call = self_call(|mock| {
+        let _ = dao::api::spend(mock, &bob(), &U256::from(10u64));
+    });
+    let failing = Call {
+        contract: Some(Address::from([9u8; 20])),
+        ..empty_call()
+    };
+    let batch = Batch {
+        calls: vec![spend_call, failing],
+        mode: BatchMode::AllOrNothing,
+    };
+
+    let deposit = dao::track(0).unwrap().decision_deposit;
+    with_engine(|e| {
+        e.set_caller([1u8; 20]);
+        e.value_transferred = deposit;
+    });
+    let prop_id = dao::submit_batch_proposal(batch).unwrap();
+    assert_eq!(dao::deposit_proposal(prop_id), Ok(()));
+    with_engine(|e| e.value_transferred = U256::ZERO);
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+
+    // 经合约调用执行，第二个调用失败时第一个 spend 一并回滚
+    with_engine(|e| e.set_contract(Address::from(gov())));
+    assert!(dao::api::exec_proposal(&dao_addr, &prop_id).is_err());
+    with_engine(|e| e.set_contract(dao_addr));
+    assert_eq!(dao::get_spend(0), None);
+    assert!(!dao::proposal(prop_id).unwrap().executed);
+    assert_eq!(dao::enactment_queue(), vec![(prop_id, 0)]);
+
+    // 即使没有回滚，失败的执行也不会标记提案已执行
+    assert_eq!(dao::exec_proposal(prop_id), Err(Error::CallFailed));
+    let prop = dao::proposal(prop_id).unwrap();
+    assert!(!prop.executed);
+    assert_eq!(prop.result, None);
+}
+
+#[test]
+fn max_deciding_queues_and_promotes() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let track = Track {
+        max_deciding: 1,
+        ..voting_track()
+    };
+    let _ = dao::new_with_track(users, true, None, track);
+    let first = open_proposal();
+    let second = open_proposal();
+
+    assert_eq!(dao::proposal(first).unwrap().status, PropStatus::Ongoing);
+    let queued = dao::proposal(second).unwrap();
+    assert_eq!(queued.status, PropStatus::Pending);
+    assert_eq!(queued.decide_block, None);
+    assert_eq!(dao::deciding_of(0), (1, vec![second]));
+    with_engine(|e| e.value_transferred = U256::from(1u64));
+    assert_eq!(
+        dao::deposit_proposal(second),
+        Err(Error::InvalidProposalStatus)
+    );
+
+    // 第一个提案通过后释放名额，排队提案自动进入决策期
+    let _ = dao::vote(first, Opinion::YES, U256::from(100u64), 1).unwrap();
+    assert_eq!(
+        dao::proposal(first).unwrap().status,
+        PropStatus::Approved(0)
+    );
+    let promoted = dao::proposal(second).unwrap();
+    assert_eq!(promoted.status, PropStatus::Ongoing);
+    assert_eq!(promoted.decide_block, Some(0));
+    assert_eq!(dao::deciding_of(0), (1, vec![]));
+}
+
+#[test]
+fn delegated_weight_counts_for_delegate() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::delegate(alice(), None), Ok(()));
+    assert_eq!(dao::lock_balance_of(bob()), U256::from(50u64));
+    // 委托锁定的代币不可转账
+    assert_eq!(
+        dao::transfer(alice(), U256::from(1u64)),
+        Err(Error::LowBalance)
+    );
+    assert_eq!(dao::delegated_power_of(alice(), 0), U256::from(50u64));
+
+    let prop_id = open_proposal();
+    let vote_id = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    let vote = dao::vote_info(vote_id).unwrap();
+    assert_eq!(vote.vote_weight, U256::from(150u64));
+    assert_eq!(vote.delegated, U256::from(50u64));
+    assert_eq!(
+        dao::proposal(prop_id).unwrap().status,
+        PropStatus::Approved(0)
+    );
+
+    // 委托权重已计入投票，解锁前不可撤销
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::delegations_of(bob())[0].locked_until, 10);
+    assert_eq!(dao::undelegate(None), Err(Error::DelegationLocked));
+}
+
+#[test]
+fn delegator_cannot_vote_directly() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let prop_id = open_proposal();
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::delegate(alice(), Some(0)), Ok(()));
+    assert_eq!(
+        dao::vote(prop_id, Opinion::NO, U256::ZERO, 1),
+        Err(Error::VoteDelegated)
+    );
+}
+
+#[test]
+fn undelegate_releases_lock() {
+    setup();
+    let carol = Address::from([3u8; 20]);
+    let users = vec![
+        (alice(), U256::from(100u64)),
+        (bob(), U256::from(50u64)),
+        (carol, U256::from(10u64)),
+    ];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::undelegate(None), Err(Error::DelegationNotFound));
+    assert_eq!(dao::delegate(alice(), None), Ok(()));
+    assert_eq!(dao::delegate(carol, Some(0)), Ok(()));
+    assert_eq!(dao::delegations_of(bob()).len(), 2);
+    // 单 track 委托优先于全局委托
+    assert_eq!(dao::delegated_power_of(alice(), 0), U256::ZERO);
+    assert_eq!(dao::delegated_power_of(carol, 0), U256::from(50u64));
+
+    assert_eq!(dao::undelegate(Some(0)), Ok(()));
+    assert_eq!(dao::lock_balance_of(bob()), U256::from(50u64));
+    assert_eq!(dao::delegated_power_of(alice(), 0), U256::from(50u64));
+    assert_eq!(dao::undelegate(None), Ok(()));
+    assert_eq!(dao::lock_balance_of(bob()), U256::ZERO);
+    assert!(dao::delegations_of(bob()).is_empty());
+}
+
+#[test]
+fn leaving_member_drops_delegations() {
+    setup();
+    let carol = Address::from([3u8; 20]);
+    let users = vec![
+        (alice(), U256::from(100u64)),
+        (bob(), U256::from(50u64)),
+        (carol, U256::from(10u64)),
+    ];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::delegate(alice(), None), Ok(()));
+    assert_eq!(dao::delegate(carol, Some(0)), Ok(()));
+    // 全局委托在未单独委托的 track 上生效
+    assert_eq!(dao::delegated_power_of(alice(), 1), U256::from(50u64));
+    assert_eq!(dao::leave_with_burn(), Ok(()));
+    assert!(dao::delegations_of(bob()).is_empty());
+    assert_eq!(dao::delegated_power_of(carol, 0), U256::ZERO);
+    assert_eq!(dao::delegated_power_of(alice(), 1), U256::ZERO);
+
+    with_engine(|e| e.set_caller([3u8; 20]));
+    assert_eq!(dao::delegate(alice(), None), Ok(()));
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::delete(carol), Ok(()));
+    assert!(dao::delegations_of(carol).is_empty());
+    assert_eq!(dao::delegated_power_of(alice(), 0), U256::ZERO);
+
+    // 受托人离开时，委托给他的委托一并撤销并释放锁定
+    let dave = Address::from([4u8; 20]);
+    assert_eq!(dao::join(dave, U256::from(30u64)), Ok(()));
+    with_engine(|e| e.set_caller([4u8; 20]));
+    assert_eq!(dao::delegate(alice(), Some(0)), Ok(()));
+    assert_eq!(dao::lock_balance_of(dave), U256::from(30u64));
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::leave_with_burn(), Ok(()));
+    assert!(dao::delegations_of(dave).is_empty());
+    assert_eq!(dao::lock_balance_of(dave), U256::ZERO);
+}
+
+#[test]
+fn delegation_is_a_single_hop() {
+    setup();
+    let carol = Address::from([3u8; 20]);
+    let users = vec![
+        (alice(), U256::from(10u64)),
+        (bob(), U256::from(20u64)),
+        (carol, U256::from(40u64)),
+    ];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::delegate(alice(), None), Err(Error::InvalidDelegate));
+    assert_eq!(dao::delegate(bob(), None), Ok(()));
+    assert_eq!(dao::delegate(bob(), None), Err(Error::DelegationExisted));
+    // 已收到委托的成员不能再委托，也不能委托给已委托他人的成员
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::delegate(carol, Some(0)), Err(Error::DelegationTooDeep));
+    assert_eq!(dao::delegate(alice(), None), Err(Error::DelegationTooDeep));
+    with_engine(|e| e.set_caller([3u8; 20]));
+    assert_eq!(
+        dao::delegate(alice(), Some(0)),
+        Err(Error::DelegationTooDeep)
+    );
+    assert_eq!(dao::delegate(bob(), Some(0)), Ok(()));
+    assert_eq!(dao::delegated_power_of(bob(), 0), U256::from(50u64));
+    assert_eq!(dao::delegated_power_of(bob(), 1), U256::from(10u64));
+
+    // 撤销后链断开，可以重新委托
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::undelegate(None), Ok(()));
+    with_engine(|e| e.set_caller([3u8; 20]));
+    assert_eq!(dao::undelegate(Some(0)), Ok(()));
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::delegate(carol, None), Ok(()));
+}
+
+#[test]
+fn locked_global_delegation_cannot_be_overridden_per_track() {
+    setup();
+    let carol = Address::from([3u8; 20]);
+    let users = vec![
+        (alice(), U256::from(100u64)),
+        (bob(), U256::from(20u64)),
+        (carol, U256::from(40u64)),
+    ];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::delegate(alice(), None), Ok(()));
+    let prop_id = open_proposal();
+
+    // alice 投票后 bob 的全局委托被锁定，不能改委托 carol 在同一提案上再计一次
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::delegate(carol, Some(0)), Err(Error::DelegationLocked));
+    with_engine(|e| e.set_caller([3u8; 20]));
+    let vote_id = dao::vote(prop_id, Opinion::NO, U256::from(40u64), 1).unwrap();
+    assert_eq!(
+        dao::vote_info(vote_id).unwrap().vote_weight,
+        U256::from(40u64)
+    );
+}
+
+#[test]
+fn conviction_scales_weight_and_lock() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let prop_id = open_proposal();
+
+    assert_eq!(
+        dao::vote(prop_id, Opinion::NO, U256::from(10u64), 7),
+        Err(Error::InvalidConviction)
+    );
+    let none = dao::vote(prop_id, Opinion::NO, U256::from(100u64), 0).unwrap();
+    let vote = dao::vote_info(none).unwrap();
+    assert_eq!(vote.vote_weight, U256::from(10u64));
+    assert_eq!(vote.unlock_block, 10);
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    let max = dao::vote(prop_id, Opinion::NO, U256::from(100u64), 6).unwrap();
+    let vote = dao::vote_info(max).unwrap();
+    assert_eq!(vote.vote_weight, U256::from(600u64));
+    assert_eq!(vote.unlock_block, 10 + 32 * 10);
+    let tally = dao::proposal(prop_id).unwrap().tally;
+    assert_eq!(tally.nays, U256::from(610u64));
+    // 支持率按实际质押计算，不受 conviction 影响
+    assert_eq!(tally.support, U256::from(200u64));
+}
+
+#[test]
+fn track_conviction_set_by_gov() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    assert_eq!(dao::track_conviction(0).unwrap().levels.len(), 7);
+
+    let conviction = Conviction {
+        lock_period: 3,
+        levels: vec![ConvictionLevel {
+            multiplier: 20000,
+            lock_periods: 2,
+        }],
+    };
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(
+        dao::set_track_conviction(0, conviction.clone()),
+        Err(Error::MustCallByGov)
+    );
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(
+        dao::set_track_conviction(
+            0,
+            Conviction {
+                lock_period: 3,
+                levels: vec![],
+            }
+        ),
+        Err(Error::InvalidConviction)
+    );
+    assert_eq!(dao::set_track_conviction(0, conviction.clone()), Ok(()));
+    assert_eq!(dao::track_conviction(0), Some(conviction));
+
+    let prop_id = open_proposal();
+    let vote_id = dao::vote(prop_id, Opinion::NO, U256::from(10u64), 0).unwrap();
+    let vote = dao::vote_info(vote_id).unwrap();
+    assert_eq!(vote.vote_weight, U256::from(20u64));
+    assert_eq!(vote.unlock_block, 10 + 2 * 3);
+}
+
+fn free_track() -> Track {
+    Track {
+        decision_deposit: U256::ZERO,
+        max_deciding: 1,
+        ..voting_track()
+    }
+}
+
+/// 借助模拟合约取得 DAO message 的调用数据，构造 DAO 调用自身的 Call
+fn self_call(send: impl FnOnce(&Address)) -> Call {
+    let mock = Address::from([8u8; 20]);
+    let captured = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let sink = captured.clone();
+    with_engine(|e| {
+        e.register_contract(mock, move || {
+            let size = env().call_data_size() as usize;
+            *sink.borrow_mut() = env().call_data_copy(0, size);
+            env().return_value(ReturnFlags::empty(), &[]);
+        })
+    });
+    send(&mock);
+    let data = captured.borrow().clone();
+    Call {
+        selector: data[..4].try_into().unwrap(),
+        input: data[4..].to_vec(),
+        allow_reentry: true,
+        ..empty_call()
+    }
+}
+
+/// alice 发起并执行委员会动议，DAO 以自身身份真实调用该 message
+fn run_motion(council_id: u16, motion_call: Call) -> CallResult {
+    with_engine(|e| {
+        e.register_contract(Address::from(e.current_contract), call);
+        e.set_caller([1u8; 20]);
+    });
+    let motion_id = dao::propose_motion(council_id, motion_call).unwrap();
+    dao::exec_motion(motion_id).unwrap()
+}
+
+/// 动议调用失败时 revert 数据中的错误
+fn motion_error(result: &CallResult) -> Error {
+    assert!(!result.success);
+    let decoded: Result<(), Error> =
+        parity_scale_codec::Decode::decode(&mut &result.output[..]).unwrap();
+    decoded.unwrap_err()
+}
+
+#[test]
+fn council_spends_count_against_council_limit() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, Some(Address::from(gov())), instant_track());
+    with_engine(|e| e.set_caller(e.current_contract));
+    let council = Council {
+        calls: vec![(None, None)],
+        max_spend: U256::from(50u64),
+        ..council_of(vec![alice()], 1)
+    };
+    let _ = dao::add_council(council);
+    let to_bob = |amount: u64| {
+        self_call(|mock| {
+            let _ = dao::api::spend(mock, &bob(), &U256::from(amount));
+        })
+    };
+
+    // 动议执行的支出记在提出人名下，且不超过委员会限额
+    assert!(run_motion(0, to_bob(30)).success);
+    assert_eq!(dao::get_spend(0).unwrap().caller, alice());
+    assert_eq!(
+        motion_error(&run_motion(0, to_bob(51))),
+        Error::MaxBalanceOverflow
+    );
+    assert_eq!(dao::get_spend(1), None);
+    // 限额按动议分别计算
+    assert!(run_motion(0, to_bob(50)).success);
+    assert_eq!(dao::get_spend(1).unwrap().amount, U256::from(50u64));
+    // 治理直接支出不受委员会限额约束
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(
+        dao::get_spend(dao::spend(bob(), U256::from(500u64)).unwrap())
+            .unwrap()
+            .caller,
+        gov().into()
+    );
+}
+
+fn emergency_setup() -> CallId {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let track = Track {
+        min_enactment_period: 5,
+        ..voting_track()
+    };
+    let _ = dao::new_with_track(users, true, None, track);
+    with_engine(|e| e.set_caller(e.current_contract));
+    let council = Council {
+        calls: vec![(None, None)],
+        ..council_of(vec![alice()], 1)
+    };
+    let _ = dao::add_council(council.clone());
+    let _ = dao::add_council(council);
+    assert_eq!(
+        dao::set_emergency_council(Some(2), 100),
+        Err(Error::CouncilNotFound)
+    );
+    assert_eq!(dao::set_emergency_council(Some(0), 100), Ok(()));
+    assert_eq!(dao::emergency_council(), (Some(0), 100));
+    open_proposal()
+}
+
+#[test]
+fn emergency_veto_blocks_enactment_and_resubmission() {
+    let prop_id = emergency_setup();
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    assert_eq!(dao::enactment_queue(), vec![(prop_id, 5)]);
+    let veto = self_call(|mock| {
+        let _ = dao::api::veto_proposal(mock, &prop_id);
+    });
+
+    // 治理自身或其他委员会的动议不能否决
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::veto_proposal(prop_id), Err(Error::MustCallByEmergency));
+    assert_eq!(
+        motion_error(&run_motion(1, veto.clone())),
+        Error::MustCallByEmergency
+    );
+
+    assert!(run_motion(0, veto.clone()).success);
+    assert_eq!(
+        dao::proposal(prop_id).unwrap().status,
+        PropStatus::Vetoed(0)
+    );
+    assert!(dao::enactment_queue().is_empty());
+    assert_eq!(
+        motion_error(&run_motion(0, veto)),
+        Error::InvalidProposalStatus
+    );
+    let call = empty_call();
+    let dao_addr = Address::from(with_engine(|e| e.current_contract));
+    let call_hash = alloy_core::primitives::keccak256(
+        (dao_addr, call.selector, &call.input, call.amount).encode(),
+    )
+    .0;
+    assert_eq!(dao::vetoed_until(call_hash), Some(100));
+
+    assert_eq!(
+        dao::exec_proposal(prop_id),
+        Err(Error::ProposalNotConfirmed)
+    );
+    assert_eq!(dao::submit_proposal(empty_call()), Err(Error::CallVetoed));
+    // 只改 gas 限制或重入标记，或混入批量中，仍视为同一调用
+    let tweaked = Call {
+        ref_time_limit: 1,
+        allow_reentry: true,
+        ..empty_call()
+    };
+    assert_eq!(dao::submit_proposal(tweaked), Err(Error::CallVetoed));
+    // 显式写出 DAO 地址与 None 指向同一合约
+    let explicit = Call {
+        contract: Some(dao_addr),
+        ..empty_call()
+    };
+    assert_eq!(dao::submit_proposal(explicit), Err(Error::CallVetoed));
+    let other = Call {
+        selector: [2u8; 4],
+        ..empty_call()
+    };
+    let batch = Batch {
+        calls: vec![other.clone(), empty_call()],
+        mode: BatchMode::BestEffort,
+    };
+    assert_eq!(dao::submit_batch_proposal(batch), Err(Error::CallVetoed));
+    assert!(dao::submit_proposal(other).is_ok());
+}
+
+#[test]
+fn vetoed_batch_cannot_be_split_or_reordered() {
+    let _ = emergency_setup();
+    let first = Call {
+        selector: [2u8; 4],
+        ..empty_call()
+    };
+    let second = Call {
+        selector: [3u8; 4],
+        ..empty_call()
+    };
+    let batch = Batch {
+        calls: vec![first.clone(), second.clone()],
+        mode: BatchMode::AllOrNothing,
+    };
+    let deposit = dao::track(0).unwrap().decision_deposit;
+    with_engine(|e| {
+        e.set_caller([1u8; 20]);
+        e.value_transferred = deposit;
+    });
+    let prop_id = dao::submit_batch_proposal(batch).unwrap();
+    assert_eq!(dao::deposit_proposal(prop_id), Ok(()));
+    with_engine(|e| e.value_transferred = U256::ZERO);
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+
+    let veto = self_call(|mock| {
+        let _ = dao::api::veto_proposal(mock, &prop_id);
+    });
+    assert!(run_motion(0, veto).success);
+
+    assert_eq!(dao::submit_proposal(first.clone()), Err(Error::CallVetoed));
+    assert_eq!(dao::submit_proposal(second.clone()), Err(Error::CallVetoed));
+    let reordered = Batch {
+        calls: vec![second, first],
+        mode: BatchMode::BestEffort,
+    };
+    assert_eq!(
+        dao::submit_batch_proposal(reordered),
+        Err(Error::CallVetoed)
+    );
+}
+
+#[test]
+fn fast_track_caps_track_periods() {
+    let prop_id = emergency_setup();
+    let fast_track = |period: BlockNumber| {
+        self_call(|mock| {
+            let _ = dao::api::fast_track(mock, &prop_id, &period);
+        })
+    };
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::fast_track(prop_id, 2), Err(Error::MustCallByEmergency));
+    assert_eq!(
+        motion_error(&run_motion(1, fast_track(2))),
+        Error::MustCallByEmergency
+    );
+
+    assert_eq!(
+        motion_error(&run_motion(0, fast_track(0))),
+        Error::InvalidFastTrack
+    );
+    assert_eq!(
+        motion_error(&run_motion(0, fast_track(10))),
+        Error::InvalidFastTrack
+    );
+    assert!(run_motion(0, fast_track(2)).success);
+    assert_eq!(dao::fast_track_of(prop_id), Some(2));
+
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    // 执行等待期被快速通道缩短
+    assert_eq!(dao::enactment_queue(), vec![(prop_id, 2)]);
+
+    assert_eq!(
+        motion_error(&run_motion(0, fast_track(1))),
+        Error::InvalidProposalStatus
+    );
+}
+
+#[test]
+fn cancel_proposal_frees_deciding_slot() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, free_track());
+    let first = open_proposal();
+    let second = open_proposal();
+    let third = open_proposal();
+    assert_eq!(dao::deciding_of(0), (1, vec![second, third]));
+
+    assert_eq!(dao::cancel_proposal(first), Err(Error::MustCallByGov));
+    with_engine(|e| e.set_caller(e.current_contract));
+    // 撤销排队中的提案
+    assert_eq!(dao::cancel_proposal(second), Ok(()));
+    assert_eq!(dao::deciding_of(0), (1, vec![third]));
+    // 撤销决策中的提案，排队提案补位
+    assert_eq!(dao::cancel_proposal(first), Ok(()));
+    assert_eq!(dao::proposal(first).unwrap().status, PropStatus::Canceled);
+    assert_eq!(dao::proposal(third).unwrap().status, PropStatus::Ongoing);
+    assert_eq!(dao::deciding_of(0), (1, vec![]));
+    assert_eq!(
+        dao::cancel_proposal(first),
+        Err(Error::InvalidProposalStatus)
+    );
+}
+
+#[test]
+fn kill_proposal_slashes_deposit() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let prop_id = open_proposal();
+    assert_eq!(dao::proposal(prop_id).unwrap().deposit, U256::from(1u64));
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::kill_proposal(prop_id), Ok(()));
+    let prop = dao::proposal(prop_id).unwrap();
+    assert_eq!(prop.status, PropStatus::Killed);
+    assert_eq!(prop.deposit, U256::ZERO);
+    assert_eq!(dao::deciding_of(0), (0, vec![]));
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::refund_deposit(prop_id), Err(Error::PropNotEnd));
+}
+
+#[test]
+fn refund_deposit_checks_caller_and_status() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_track(users, true, None, free_track());
+    let prop_id = open_proposal();
+    assert_eq!(dao::refund_deposit(prop_id), Err(Error::PropNotEnd));
+
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    with_engine(|e| e.set_caller([2u8; 20]));
+    let _ = dao::vote(prop_id, Opinion::YES, U256::from(50u64), 1).unwrap();
+    assert_eq!(
+        dao::proposal(prop_id).unwrap().status,
+        PropStatus::Approved(0)
+    );
+    assert_eq!(
+        dao::refund_deposit(prop_id),
+        Err(Error::InvalidProposalCaller)
+    );
+    // 押金为 0 时没有可退还的部分
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::refund_deposit(prop_id), Err(Error::InvalidDeposit));
+}
+
+#[test]
+fn balance_checkpoints_follow_transfers() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    assert_eq!(dao::balance_of_at(alice(), 0), U256::from(100u64));
+    assert_eq!(dao::total_supply_at(0), U256::from(150u64));
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::transfer(bob(), U256::from(30u64)), Ok(()));
+    assert_eq!(dao::burn(U256::from(10u64)), Ok(()));
+    // 同一区块内的变动覆盖检查点
+    assert_eq!(dao::balance_of_at(alice(), 0), U256::from(60u64));
+    assert_eq!(dao::balance_of_at(bob(), 0), U256::from(80u64));
+    assert_eq!(dao::balance_of_at(bob(), 1000), U256::from(80u64));
+    assert_eq!(dao::total_supply_at(0), U256::from(140u64));
+    assert_eq!(dao::balance_of_at(Address::from([3u8; 20]), 0), U256::ZERO);
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::delete(bob()), Ok(()));
+    assert_eq!(dao::balance_of_at(bob(), 0), U256::ZERO);
+    assert_eq!(dao::total_supply_at(0), U256::from(60u64));
+}
+
+#[test]
+fn voting_power_ignores_changes_after_submission() {
+    setup();
+    let carol = Address::from([3u8; 20]);
+    let users = vec![
+        (alice(), U256::from(100u64)),
+        (bob(), U256::from(100u64)),
+        (carol, U256::from(10u64)),
+    ];
+    let _ = dao::new_with_track(users, true, None, voting_track());
+    let prop_id = open_proposal();
+
+    // 提交之后（同一区块内）的转账与销毁不影响快照
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::transfer(carol, U256::from(50u64)), Ok(()));
+    assert_eq!(dao::burn(U256::from(10u64)), Ok(()));
+    assert_eq!(dao::total_supply(), U256::from(200u64));
+    assert_eq!(
+        dao::proposal_tally(prop_id).unwrap().total_issuance,
+        U256::from(210u64)
+    );
+
+    with_engine(|e| e.set_caller([3u8; 20]));
+    assert_eq!(
+        dao::vote(prop_id, Opinion::YES, U256::from(60u64), 1),
+        Err(Error::LowBalance)
+    );
+    // 委托权重同样按快照余额计算
+    assert_eq!(dao::delegate(bob(), None), Ok(()));
+    with_engine(|e| e.set_caller([2u8; 20]));
+    let vote_id = dao::vote(prop_id, Opinion::YES, U256::from(100u64), 1).unwrap();
+    assert_eq!(
+        dao::vote_info(vote_id).unwrap().vote_weight,
+        U256::from(110u64)
+    );
+}
+
+fn badge_info() -> TokenInfo {
+    TokenInfo {
+        name: b"Badge".to_vec(),
+        symbol: b"BDG".to_vec(),
+        decimals: 0,
+    }
+}
+
+#[test]
+fn create_and_mint_secondary_token() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::create_token(badge_info()), Err(Error::MustCallByGov));
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    let token_id = dao::create_token(badge_info()).unwrap();
+    assert_eq!(token_id, 1);
+    assert_eq!(dao::token(token_id), Some(badge_info()));
+    assert_eq!(
+        dao::mint_token(0, alice(), U256::from(1u64)),
+        Err(Error::InvalidTokenId)
+    );
+    assert_eq!(
+        dao::mint_token(9, alice(), U256::from(1u64)),
+        Err(Error::TokenNotFound)
+    );
+    assert_eq!(
+        dao::mint_token(token_id, alice(), U256::from(100u64)),
+        Ok(())
+    );
+    assert_eq!(dao::member_token(alice(), token_id), U256::from(100u64));
+    assert_eq!(dao::token_supply(token_id), U256::from(100u64));
+    // 治理代币不受影响
+    assert_eq!(dao::total_supply(), U256::from(100u64));
+}
+
+#[test]
+fn secondary_token_transfer_approve_and_burn() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+    with_engine(|e| e.set_caller(e.current_contract));
+    let token_id = dao::create_token(badge_info()).unwrap();
+    let _ = dao::mint_token(token_id, alice(), U256::from(100u64));
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(
+        dao::transfer_token(token_id, bob(), U256::from(30u64)),
+        Ok(())
+    );
+    assert_eq!(
+        dao::transfer_token(token_id, bob(), U256::from(100u64)),
+        Err(Error::LowBalance)
+    );
+    assert_eq!(
+        dao::approve_token(token_id, bob(), U256::from(20u64)),
+        Ok(())
+    );
+    assert_eq!(
+        dao::token_allowance(token_id, alice(), bob()),
+        U256::from(20u64)
+    );
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(
+        dao::transfer_token_from(token_id, alice(), bob(), U256::from(25u64)),
+        Err(Error::InsufficientAllowance)
+    );
+    assert_eq!(
+        dao::transfer_token_from(token_id, alice(), bob(), U256::from(20u64)),
+        Ok(())
+    );
+    assert_eq!(dao::member_token(alice(), token_id), U256::from(50u64));
+    assert_eq!(dao::member_token(bob(), token_id), U256::from(50u64));
+    assert_eq!(dao::token_allowance(token_id, alice(), bob()), U256::ZERO);
+
+    assert_eq!(dao::burn_token(token_id, U256::from(10u64)), Ok(()));
+    assert_eq!(dao::token_supply(token_id), U256::from(90u64));
+    assert_eq!(
+        dao::burn_token(0, U256::from(1u64)),
+        Err(Error::InvalidTokenId)
+    );
+}
+
+fn sol_address(address: Address) -> alloy_core::primitives::Address {
+    alloy_core::primitives::Address::from(address.0)
+}
+
+#[test]
+fn erc20_abi_views() {
+    use crate::erc20::{balanceOfCall, decimalsCall, nameCall, symbolCall, totalSupplyCall};
+    use alloy_core::primitives::U256 as SolU256;
+    use alloy_core::sol_types::SolCall;
+
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+
+    let input = balanceOfCall {
+        owner: sol_address(alice()),
+    }
+    .abi_encode();
+    let out = crate::erc20::dispatch(&input).unwrap();
+    assert_eq!(
+        balanceOfCall::abi_decode_returns(&out).unwrap(),
+        SolU256::from(100u64)
+    );
+    let out = crate::erc20::dispatch(&totalSupplyCall {}.abi_encode()).unwrap();
+    assert_eq!(
+        totalSupplyCall::abi_decode_returns(&out).unwrap(),
+        SolU256::from(150u64)
+    );
+    let out = crate::erc20::dispatch(&decimalsCall {}.abi_encode()).unwrap();
+    assert_eq!(decimalsCall::abi_decode_returns(&out).unwrap(), 18);
+    let out = crate::erc20::dispatch(&nameCall {}.abi_encode()).unwrap();
+    assert_eq!(nameCall::abi_decode_returns(&out).unwrap(), "WeTEE DAO");
+    let out = crate::erc20::dispatch(&symbolCall {}.abi_encode()).unwrap();
+    assert_eq!(symbolCall::abi_decode_returns(&out).unwrap(), "DAO");
+
+    // 空调用数据视为原生代币转入
+    assert_eq!(crate::erc20::dispatch(&[]), Ok(vec![]));
+    assert_eq!(
+        crate::erc20::dispatch(&[0xde, 0xad, 0xbe, 0xef]),
+        Err(Error::InvalidCallData)
+    );
+}
+
+#[test]
+fn erc20_abi_transfer_and_approve() {
+    use crate::erc20::{allowanceCall, approveCall, transferCall, transferFromCall};
+    use alloy_core::primitives::U256 as SolU256;
+    use alloy_core::sol_types::SolCall;
+
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+
+    with_engine(|e| e.set_caller([1u8; 20]));
+    let input = transferCall {
+        to: sol_address(bob()),
+        amount: SolU256::from(30u64),
+    }
+    .abi_encode();
+    let out = crate::erc20::dispatch(&input).unwrap();
+    assert!(transferCall::abi_decode_returns(&out).unwrap());
+    assert_eq!(dao::balance_of(alice()), U256::from(70u64));
+    assert_eq!(dao::balance_of(bob()), U256::from(80u64));
+
+    let input = transferCall {
+        to: sol_address(bob()),
+        amount: SolU256::from(1000u64),
+    }
+    .abi_encode();
+    assert_eq!(crate::erc20::dispatch(&input), Err(Error::LowBalance));
+
+    let input = approveCall {
+        spender: sol_address(bob()),
+        amount: SolU256::from(20u64),
+    }
+    .abi_encode();
+    assert!(crate::erc20::dispatch(&input).is_ok());
+    let input = allowanceCall {
+        owner: sol_address(alice()),
+        spender: sol_address(bob()),
+    }
+    .abi_encode();
+    let out = crate::erc20::dispatch(&input).unwrap();
+    assert_eq!(
+        allowanceCall::abi_decode_returns(&out).unwrap(),
+        SolU256::from(20u64)
+    );
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    let input = transferFromCall {
+        from: sol_address(alice()),
+        to: sol_address(bob()),
+        amount: SolU256::from(20u64),
+    }
+    .abi_encode();
+    assert!(crate::erc20::dispatch(&input).is_ok());
+    assert_eq!(dao::balance_of(bob()), U256::from(100u64));
+    assert_eq!(dao::allowance(alice(), bob()), U256::ZERO);
+}
+
+#[test]
+fn event_topics_index_involved_addresses() {
+    let event = Event::Transfer {
+        from: alice(),
+        to: bob(),
+        amount: U256::from(10u64),
+    };
+    let topics = event.topics();
+    assert_eq!(topics.len(), 3);
+    assert_eq!(topics[0], alloy_core::primitives::keccak256(b"Transfer").0);
+    assert_eq!(&topics[1][12..], alice().0.as_slice());
+    assert_eq!(topics[1][..12], [0u8; 12]);
+    assert_eq!(&topics[2][12..], bob().0.as_slice());
+
+    let topics = Event::TrackAdded { track_id: 1 }.topics();
+    assert_eq!(topics.len(), 1);
+    assert_ne!(topics[0], alloy_core::primitives::keccak256(b"Transfer").0);
+
+    let topics = Event::TrackRuleSet {
+        contract: None,
+        selector: Some([1, 2, 3, 4]),
+        track_id: 0,
+    }
+    .topics();
+    assert_eq!(topics.len(), 1);
+}
+
+#[test]
+fn proxy_upgrade_by_governance_keeps_state() {
+    setup();
+    let proxy_addr = Address::from([0x50u8; 20]);
+    let impl_v1 = Address::from([0x51u8; 20]);
+    let impl_v2 = Address::from([0x52u8; 20]);
+    // 借助模拟合约取得代理 upgrade 的调用数据
+    let mock = Address::from([8u8; 20]);
+    let captured = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let sink = captured.clone();
+    with_engine(|e| {
+        e.register_contract(proxy_addr, proxy::call);
+        e.register_contract(impl_v1, call);
+        e.register_contract(impl_v2, call);
+        e.register_contract(mock, move || {
+            let size = env().call_data_size() as usize;
+            *sink.borrow_mut() = env().call_data_copy(0, size);
+            env().return_value(ReturnFlags::empty(), &[]);
+        });
+        e.set_contract(proxy_addr);
+        e.set_caller(gov());
+    });
+    let _ = proxy::proxy::api::upgrade(&mock, &impl_v2);
+    let data = captured.borrow().clone();
+
+    let _ = proxy::proxy::new(impl_v1, None);
+    // 代理管理员转移给 DAO 自身，之后只能通过治理升级
+    assert_eq!(proxy::proxy::transfer_admin(proxy_addr), Ok(()));
+
+    // 构造函数无法经由代理调用，部署后通过代理调用 init 初始化
+    with_engine(|e| e.set_contract(Address::from(gov())));
+    let users = vec![(alice(), U256::from(100u64))];
+    let sudo = Some(Address::from(gov()));
+    assert_eq!(
+        dao::api::init(&proxy_addr, &users, &true, &sudo, &None),
+        Ok(Ok(()))
+    );
+    // 重复调用不会重新初始化
+    assert_eq!(
+        dao::api::init(
+            &proxy_addr,
+            &vec![(bob(), U256::from(1u64))],
+            &true,
+            &None,
+            &None
+        ),
+        Ok(Ok(()))
+    );
+    assert_eq!(dao::api::storage_version(&proxy_addr), Ok(2));
+
+    let upgrade = Call {
+        contract: Some(proxy_addr),
+        selector: data[..4].try_into().unwrap(),
+        input: data[4..].to_vec(),
+        ..empty_call()
+    };
+    // 实现合约运行在代理上下文中，对代理的调用属于重入，未允许重入时被拒绝
+    let result = dao::api::sudo(&proxy_addr, &upgrade).unwrap().unwrap();
+    assert!(!result.success);
+    assert_eq!(
+        proxy::proxy::api::get_implementation(&proxy_addr),
+        Ok(impl_v1)
+    );
+    let upgrade = Call {
+        allow_reentry: true,
+        ..upgrade
+    };
+    let result = dao::api::sudo(&proxy_addr, &upgrade).unwrap().unwrap();
+    assert!(result.success);
+    assert_eq!(
+        proxy::proxy::api::get_implementation(&proxy_addr),
+        Ok(impl_v2)
+    );
+    assert_eq!(dao::api::migrate(&proxy_addr, &100), Ok(Ok(0)));
+
+    // 经代理调用新实现，原有状态仍在
+    assert_eq!(
+        dao::api::balance_of(&proxy_addr, &alice()),
+        Ok(U256::from(100u64))
+    );
+    assert_eq!(dao::api::list(&proxy_addr), Ok(vec![alice()]));
+}
+
+#[test]
+fn vesting_releases_linearly_after_cliff() {
+    let vesting = Vesting {
+        amount: U256::from(1000u64),
+        claimed: U256::ZERO,
+        start: 100,
+        cliff: 20,
+        duration: 100,
+    };
+    assert_eq!(vesting.vested(0), U256::ZERO);
+    assert_eq!(vesting.vested(119), U256::ZERO);
+    assert_eq!(vesting.vested(120), U256::from(200u64));
+    assert_eq!(vesting.vested(150), U256::from(500u64));
+    assert_eq!(vesting.vested(200), U256::from(1000u64));
+    assert_eq!(vesting.vested(u32::MAX), U256::from(1000u64));
+}
+
+#[test]
+fn grant_vested_locks_new_tokens() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+    let charlie = Address::from([3u8; 20]);
+    assert_eq!(
+        dao::grant_vested(bob(), U256::from(50u64), 0, 0, 10),
+        Err(Error::MustCallByGov)
+    );
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(
+        dao::grant_vested(bob(), U256::from(50u64), 0, 20, 10),
+        Err(Error::InvalidVesting)
+    );
+    assert_eq!(
+        dao::grant_vested(bob(), U256::ZERO, 0, 0, 10),
+        Err(Error::InvalidVesting)
+    );
+    assert_eq!(
+        dao::grant_vested(bob(), U256::from(50u64), 0, 5, 10),
+        Ok(())
+    );
+    assert_eq!(
+        dao::grant_vested(alice(), U256::from(30u64), 10, 0, 10),
+        Ok(())
+    );
+    assert!(dao::list().contains(&bob()));
+    assert_eq!(dao::balance_of(bob()), U256::from(50u64));
+    assert_eq!(dao::lock_balance_of(bob()), U256::from(50u64));
+    assert_eq!(dao::balance_of(alice()), U256::from(130u64));
+    assert_eq!(dao::lock_balance_of(alice()), U256::from(30u64));
+    assert_eq!(dao::total_supply(), U256::from(180u64));
+    assert_eq!(dao::vesting_of(bob()).len(), 1);
+
+    // 未释放的代币不能转出
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(
+        dao::transfer(charlie, U256::from(1u64)),
+        Err(Error::LowBalance)
+    );
+    assert_eq!(dao::claim_vested(), Err(Error::NoVestedBalance));
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::transfer(charlie, U256::from(100u64)), Ok(()));
+    assert_eq!(
+        dao::transfer(charlie, U256::from(1u64)),
+        Err(Error::LowBalance)
+    );
+
+    // 退出并销毁时一并清除释放计划
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(dao::leave_with_burn(), Ok(()));
+    assert_eq!(dao::vesting_of(bob()), vec![]);
+    assert_eq!(dao::lock_balance_of(bob()), U256::ZERO);
+    assert_eq!(dao::total_supply(), U256::from(130u64));
+}
+
+#[test]
+fn member_registry_swap_removes_and_paginates() {
+    setup();
+    let charlie = Address::from([3u8; 20]);
+    let dave = Address::from([4u8; 20]);
+    let users = vec![
+        (alice(), U256::from(10u64)),
+        (bob(), U256::from(10u64)),
+        (charlie, U256::from(10u64)),
+        (dave, U256::from(10u64)),
+    ];
+    let _ = dao::new_with_default_track(users, true, None);
+    assert_eq!(dao::member_count(), 4);
+    assert_eq!(dao::members(None, 2), vec![(3, dave), (2, charlie)]);
+    assert_eq!(dao::members(Some(1), 5), vec![(1, bob()), (0, alice())]);
+    assert_eq!(dao::members(None, 0), vec![]);
+
+    // 删除成员后最后一个成员填补其下标
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::delete(bob()), Ok(()));
+    assert_eq!(dao::member_count(), 3);
+    assert_eq!(dao::list(), vec![alice(), dave, charlie]);
+    assert_eq!(dao::delete(charlie), Ok(()));
+    assert_eq!(dao::list(), vec![alice(), dave]);
+
+    // 重新加入的成员追加到末尾
+    assert_eq!(dao::join(bob(), U256::from(5u64)), Ok(()));
+    assert_eq!(dao::members(None, 1), vec![(2, bob())]);
+}
+
+#[test]
+fn migrate_moves_legacy_member_list() {
+    const LEGACY_MEMBERS: Storage<Vec<Address>> = storage!(b"members");
+    const STORAGE_VERSION: Storage<u32> = storage!(b"storage_version");
+    const MEMBER_BALANCES: Mapping<Address, U256> = mapping!(b"member_balances");
+    const TOTAL_ISSUANCE: Storage<U256> = storage!(b"total_issuance");
+
+    setup();
+    let _ = dao::new_with_track(vec![], true, None, voting_track());
+    assert_eq!(dao::member_count(), 0);
+    // 模拟 v0 部署：成员保存在单个 Vec 中且没有检查点，carol 只残留在列表里
+    let carol = Address::from([3u8; 20]);
+    MEMBER_BALANCES.set(&alice(), &U256::from(10u64));
+    MEMBER_BALANCES.set(&bob(), &U256::ZERO);
+    TOTAL_ISSUANCE.set(&U256::from(10u64));
+    LEGACY_MEMBERS.set(&vec![alice(), carol, bob()]);
+    STORAGE_VERSION.set(&0);
+
+    // 分批迁移，处理完之前不更新存储版本
+    assert_eq!(dao::migrate(2), Ok(1));
+    assert_eq!(dao::storage_version(), 0);
+    assert_eq!(dao::list(), vec![alice()]);
+    assert_eq!(dao::migrate(2), Ok(0));
+    assert_eq!(dao::storage_version(), 2);
+    assert_eq!(dao::list(), vec![alice(), bob()]);
+    assert_eq!(dao::member_count(), 2);
+    assert_eq!(LEGACY_MEMBERS.get(), Some(vec![]));
+    // 再次调用不重复迁移
+    assert_eq!(dao::migrate(2), Ok(0));
+    assert_eq!(dao::member_count(), 2);
+
+    // 迁移写入的检查点使旧持有人可以按快照投票
+    assert_eq!(dao::balance_of_at(alice(), 0), U256::from(10u64));
+    let prop_id = open_proposal();
+    assert_eq!(
+        dao::proposal_tally(prop_id).unwrap().total_issuance,
+        U256::from(10u64)
+    );
+    assert!(dao::vote(prop_id, Opinion::YES, U256::from(10u64), 1).is_ok());
+}
+
+#[test]
+fn arg_to_curve_rejects_malformed_args() {
+    let linear = CurveArg::LinearDecreasing {
+        begin: 5000,
+        end: 8000,
+        length: 10,
+    };
+    assert_eq!(arg_to_curve(linear), Err(Error::InvalidCurve));
+    let over = CurveArg::SteppedDecreasing {
+        begin: 12000,
+        end: 0,
+        step: 100,
+        period: 10,
+    };
+    assert_eq!(arg_to_curve(over), Err(Error::InvalidCurve));
+    // begin < end 时不能下溢
+    let reciprocal = CurveArg::Reciprocal {
+        x_offset_percent: Percent { v: 5000 },
+        x_scale_arg: 1,
+        begin: 10,
+        end: 20,
+    };
+    assert_eq!(arg_to_curve(reciprocal), Err(Error::InvalidCurve));
+    let bad_percent = CurveArg::Reciprocal {
+        x_offset_percent: Percent { v: 20000 },
+        x_scale_arg: 1,
+        begin: 10000,
+        end: 0,
+    };
+    assert_eq!(arg_to_curve(bad_percent), Err(Error::InvalidCurve));
+
+    let curve = arg_to_curve(CurveArg::Reciprocal {
+        x_offset_percent: Percent { v: 0 },
+        x_scale_arg: 1,
+        begin: 10000,
+        end: 0,
+    })
+    .unwrap();
+    assert!(curve.is_valid());
+    assert_eq!(curve.y(0), 10000);
+}
+
+#[test]
+fn curve_validation_and_threshold_preview() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64))];
+    let _ = dao::new_with_track(users, true, None, instant_track());
+
+    with_engine(|e| e.set_caller(e.current_contract));
+    let bad = Track {
+        min_support: Curve::Reciprocal {
+            factor: 10,
+            x_scale: 0,
+            x_offset: 1,
+            y_offset: 0,
+        },
+        ..instant_track()
+    };
+    assert_eq!(dao::add_track(bad.clone()), Err(Error::InvalidCurve));
+    assert_eq!(dao::edit_track(0, bad), Err(Error::InvalidCurve));
+    let increasing = Track {
+        min_approval: Curve::LinearDecreasing {
+            begin: 100,
+            end: 200,
+            length: 10,
+        },
+        ..instant_track()
+    };
+    assert_eq!(dao::add_track(increasing), Err(Error::InvalidCurve));
+
+    let curve = Curve::LinearDecreasing {
+        begin: 10000,
+        end: 5000,
+        length: 10,
+    };
+    assert_eq!(
+        dao::curve_preview(curve, vec![0, 5, 10, 20]),
+        Ok(vec![(0, 10000), (5, 7500), (10, 5000), (20, 5000)])
+    );
+    let over = Curve::SteppedDecreasing {
+        begin: 10001,
+        end: 0,
+        step: 1,
+        period: 1,
+    };
+    assert_eq!(dao::curve_preview(over, vec![0]), Err(Error::InvalidCurve));
+    // 步数与步长相乘不能溢出
+    let stepped = Curve::SteppedDecreasing {
+        begin: 10000,
+        end: 1000,
+        step: 10000,
+        period: 1,
+    };
+    assert_eq!(
+        dao::curve_preview(stepped, vec![0, 1, u32::MAX]),
+        Ok(vec![(0, 10000), (1, 1000), (u32::MAX, 1000)])
+    );
+
+    assert_eq!(
+        dao::track_thresholds(0, vec![0, 15, 30]),
+        Some(vec![(0, 10000, 10000), (15, 7500, 5025), (30, 5000, 50)])
+    );
+    assert_eq!(dao::track_thresholds(9, vec![0]), None);
+}
+
+#[test]
+fn rewards_follow_balance_changes() {
+    setup();
+    let users = vec![(alice(), U256::from(300u64)), (bob(), U256::from(100u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+    let charlie = Address::from([3u8; 20]);
+
+    with_engine(|e| e.value_transferred = U256::from(400u64));
+    assert_eq!(dao::deposit_rewards(), Ok(()));
+    with_engine(|e| e.value_transferred = U256::ZERO);
+    assert_eq!(dao::claimable(alice()), U256::from(300u64));
+    assert_eq!(dao::claimable(bob()), U256::from(100u64));
+    assert_eq!(dao::deposit_rewards(), Err(Error::InvalidDeposit));
+
+    // 转账后旧分红保留，新分红按新余额分配
+    with_engine(|e| e.set_caller([1u8; 20]));
+    assert_eq!(dao::transfer(bob(), U256::from(100u64)), Ok(()));
+    assert_eq!(dao::burn(U256::from(100u64)), Ok(()));
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::join(charlie, U256::from(100u64)), Ok(()));
+    assert_eq!(dao::claimable(charlie), U256::ZERO);
+
+    with_engine(|e| e.value_transferred = U256::from(800u64));
+    assert_eq!(dao::deposit_rewards(), Ok(()));
+    with_engine(|e| e.value_transferred = U256::ZERO);
+    assert_eq!(dao::claimable(alice()), U256::from(300u64 + 200));
+    assert_eq!(dao::claimable(bob()), U256::from(100u64 + 400));
+    assert_eq!(dao::claimable(charlie), U256::from(200u64));
+
+    // 退出后仍可领取已累计的分红
+    with_engine(|e| e.set_caller(e.current_contract));
+    assert_eq!(dao::delete(charlie), Ok(()));
+    assert_eq!(dao::claimable(charlie), U256::from(200u64));
+
+    with_engine(|e| e.set_caller([9u8; 20]));
+    assert_eq!(dao::claim(), Err(Error::NoRewards));
+}
+
+#[test]
+fn rewards_require_token_holders() {
+    setup();
+    let _ = dao::new_with_default_track(vec![], true, None);
+    with_engine(|e| e.value_transferred = U256::from(10u64));
+    assert_eq!(dao::deposit_rewards(), Err(Error::NoTokenHolders));
+}
+
+#[test]
+fn permit_sets_allowance_from_signature() {
+    setup();
+    let users = vec![(alice(), U256::from(100u64)), (bob(), U256::from(50u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+
+    // 模拟 ecrecover 预编译：只对 alice 以 nonce 0 签名的 Permit 恢复出 alice
+    let mut ecrecover = [0u8; 20];
+    ecrecover[19] = 1;
+    let hash = crate::erc20::permit_hash(alice(), bob(), U256::from(30u64), 0, u64::MAX);
+    with_engine(|e| {
+        e.register_contract(Address::from(ecrecover), move || {
+            let input = env().call_data_copy(0, 128);
+            let mut out = [0u8; 32];
+            if input[..32] == hash && input[63] == 27 {
+                out[12..].copy_from_slice(alice().as_ref());
+                env().return_value(ReturnFlags::empty(), &out);
+            }
+            env().return_value(ReturnFlags::empty(), &[]);
+        })
+    });
+    let signature = [0u8; 65];
+
+    // 任何人都可以代为提交签名
+    with_engine(|e| e.set_caller(gov()));
+    assert_eq!(dao::nonces(alice()), 0);
+    assert_eq!(
+        dao::permit(alice(), bob(), U256::from(31u64), u64::MAX, signature),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(
+        dao::permit(alice(), bob(), U256::from(30u64), u64::MAX, signature),
+        Ok(())
+    );
+    assert_eq!(dao::allowance(alice(), bob()), U256::from(30u64));
+    assert_eq!(dao::nonces(alice()), 1);
+    // nonce 已变化，同一签名不能重放
+    assert_eq!(
+        dao::permit(alice(), bob(), U256::from(30u64), u64::MAX, signature),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(
+        dao::permit(
+            Address::from([3u8; 20]),
+            bob(),
+            U256::from(30u64),
+            u64::MAX,
+            signature
+        ),
+        Err(Error::MemberNotExisted)
+    );
+
+    with_engine(|e| e.set_caller([2u8; 20]));
+    assert_eq!(
+        dao::transfer_from(alice(), bob(), U256::from(30u64)),
+        Ok(())
+    );
+    assert_eq!(dao::balance_of(bob()), U256::from(80u64));
+}
+
+fn hex32(s: &str) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+    }
+    out
+}
+
+#[test]
+fn permit_accepts_real_secp256k1_signature() {
+    setup();
+    with_engine(|e| e.set_contract(Address::from([0xDAu8; 20])));
+    // 私钥为 1 的账户
+    let mut owner = [0u8; 20];
+    owner.copy_from_slice(
+        &hex32("0000000000000000000000007e5f4552091a69125d5dfcb7b8c2659029395bdf")[12..],
+    );
+    let owner = Address::from(owner);
+    let users = vec![(owner, U256::from(100u64))];
+    let _ = dao::new_with_default_track(users, true, None);
+
+    // 链下独立计算的 EIP-712 摘要，域包含链 ID
+    let digest = hex32("33ebc8e0ed6ebf409a30001a713818951890d90498a84b71077e3d920b5ee37b");
+    assert_eq!(
+        crate::erc20::permit_hash(owner, bob(), U256::from(30u64), 0, u64::MAX),
+        digest
+    );
+    let r = hex32("77c3d0b2a76fa256485bf06827be55d121181b232353305765086b61d53d5d28");
+    let s = hex32("0590fa8e98cdbff088d9ddfd2fc0b1eee19cebcbfc21e470cf21de386824af18");
+    let mut signature = [0u8; 65];
+    signature[..32].copy_from_slice(&r);
+    signature[32..64].copy_from_slice(&s);
+    signature[64] = 27;
+
+    // 模拟 ecrecover 预编译：对上述签名给出真实的恢复结果
+    let mut expected = [0u8; 128];
+    expected[..32].copy_from_slice(&digest);
+    expected[63] = 27;
+    expected[64..96].copy_from_slice(&r);
+    expected[96..].copy_from_slice(&s);
+    let mut ecrecover = [0u8; 20];
+    ecrecover[19] = 1;
+    with_engine(|e| {
+        e.register_contract(Address::from(ecrecover), move || {
+            let input = env().call_data_copy(0, 128);
+            let mut out = [0u8; 32];
+            if input[..] == expected[..] {
+                out[12..].copy_from_slice(owner.as_ref());
+                env().return_value(ReturnFlags::empty(), &out);
+            }
+            env().return_value(ReturnFlags::empty(), &[]);
+        })
+    });
+
+    with_engine(|e| e.set_caller(gov()));
+    assert_eq!(
+        dao::permit(owner, bob(), U256::from(30u64), u64::MAX, signature),
+        Ok(())
+    );
+    assert_eq!(dao::allowance(owner, bob()), U256::from(30u64));
+    assert_eq!(dao::nonces(owner), 1);
+    // nonce 已递增，同一签名不能重放
+    assert_eq!(
+        dao::permit(owner, bob(), U256::from(30u64), u64::MAX, signature),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(dao::nonces(owner), 1);
+}
+
+#[test]
+fn permit_rejects_expired_deadline() {
+    assert_eq!(crate::erc20::ensure_permit_live(100, 100), Ok(()));
+    assert_eq!(
+        crate::erc20::ensure_permit_live(101, 100),
+        Err(Error::PermitExpired)
+    );
+}
+
+#[test]
+fn erc20_abi_permit_views() {
+    use crate::erc20::{DOMAIN_SEPARATORCall, noncesCall};
+    use alloy_core::primitives::U256 as SolU256;
+    use alloy_core::sol_types::SolCall;
+
+    setup();
+    let _ = dao::new_with_default_track(vec![(alice(), U256::from(100u64))], true, None);
+
+    let out = crate::erc20::dispatch(&DOMAIN_SEPARATORCall {}.abi_encode()).unwrap();
+    assert_eq!(
+        DOMAIN_SEPARATORCall::abi_decode_returns(&out).unwrap().0,
+        dao::domain_separator()
+    );
+    let input = noncesCall {
+        owner: sol_address(alice()),
+    }
+    .abi_encode();
+    let out = crate::erc20::dispatch(&input).unwrap();
+    assert_eq!(noncesCall::abi_decode_returns(&out).unwrap(), SolU256::ZERO);
+}